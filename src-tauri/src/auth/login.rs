@@ -0,0 +1,328 @@
+use std::io::Read;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use portable_pty::{CommandBuilder, NativePtySystem, PtySize, PtySystem};
+use serde_json::Value;
+use tauri::{AppHandle, Emitter};
+
+use super::{check_auth_status, copilot_config_path, host_env, parse_account, read_config};
+use crate::types::{Account, AppError, LoginChallenge, LoginEvent};
+
+/// GitHub device codes expire after 15 minutes
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+/// Output kept to find the challenge in and to explain a failure
+const OUTPUT_LIMIT: usize = 4096;
+const LOGIN_PTY_SIZE: PtySize = PtySize {
+    rows: 24,
+    cols: 120,
+    pixel_width: 0,
+    pixel_height: 0,
+};
+
+/// Runs `copilot login` in a PTY of its own and reports its progress as
+/// `LoginEvent`s on `login-event`.
+pub struct LoginManager {
+    /// Cancel flag of the login in progress, cleared when it ends
+    active: Arc<Mutex<Option<Arc<AtomicBool>>>>,
+}
+
+impl LoginManager {
+    pub fn new() -> Self {
+        Self {
+            active: Arc::new(Mutex::new(None)),
+        }
+    }
+
+    /// Start a login, cancelling one already in progress. `host` selects a GitHub
+    /// Enterprise Server instead of github.com.
+    pub fn start(
+        &self,
+        copilot_path: &str,
+        host: Option<&str>,
+        app_handle: AppHandle,
+    ) -> Result<(), AppError> {
+        let pair = NativePtySystem::default()
+            .openpty(LOGIN_PTY_SIZE)
+            .map_err(|e| AppError::PtyError(e.to_string()))?;
+
+        let mut cmd = CommandBuilder::new(copilot_path);
+        cmd.arg("login");
+        cmd.env("TERM", "xterm-256color");
+        for (key, value) in host.map(host_env).unwrap_or_default() {
+            cmd.env(key, value);
+        }
+        let child = pair
+            .slave
+            .spawn_command(cmd)
+            .map_err(|e| AppError::PtyError(e.to_string()))?;
+        drop(pair.slave);
+        let mut reader = pair
+            .master
+            .try_clone_reader()
+            .map_err(|e| AppError::PtyError(e.to_string()))?;
+
+        let cancel = Arc::new(AtomicBool::new(false));
+        if let Some(previous) = self
+            .active
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .replace(cancel.clone())
+        {
+            previous.store(true, Ordering::Relaxed);
+        }
+
+        let (tx, rx) = mpsc::channel();
+        std::thread::spawn(move || {
+            let mut buf = [0u8; 4096];
+            while let Ok(n) = reader.read(&mut buf) {
+                if n == 0 || tx.send(buf[..n].to_vec()).is_err() {
+                    break;
+                }
+            }
+        });
+        let active = self.active.clone();
+        std::thread::spawn(move || {
+            // The master must outlive the child or the CLI sees its terminal hang up
+            let _master = pair.master;
+            let emit = |event: LoginEvent| {
+                let _ = app_handle.emit("login-event", event);
+            };
+            let outcome = watch_login(child, rx, &cancel, &emit);
+            finish_login(&active, &cancel);
+            if let Some(event) = outcome {
+                emit(event);
+            }
+        });
+        Ok(())
+    }
+
+    /// Stop the login in progress. Returns false when there is none.
+    pub fn cancel(&self) -> bool {
+        match self.active.lock().unwrap_or_else(|e| e.into_inner()).take() {
+            Some(cancel) => {
+                cancel.store(true, Ordering::Relaxed);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Forget the login behind `cancel`, unless another one has replaced it
+fn finish_login(active: &Mutex<Option<Arc<AtomicBool>>>, cancel: &Arc<AtomicBool>) {
+    let mut active = active.lock().unwrap_or_else(|e| e.into_inner());
+    if active.as_ref().is_some_and(|current| Arc::ptr_eq(current, cancel)) {
+        *active = None;
+    }
+}
+
+/// Each logged-in account's `logged_in_users` entry in `config`
+fn login_entries(config: &Value) -> Vec<(Account, Value)> {
+    config
+        .get("logged_in_users")
+        .and_then(|v| v.as_array())
+        .map(|users| {
+            users
+                .iter()
+                .filter_map(|user| Some((parse_account(user)?, user.clone())))
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn login_snapshot() -> Vec<(Account, Value)> {
+    copilot_config_path()
+        .and_then(|path| read_config(&path))
+        .map(|config| login_entries(&config))
+        .unwrap_or_default()
+}
+
+/// Whether a login finished between two snapshots: an account was added, or logging
+/// in again changed its entry. Switching `last_logged_in_user`, as sessions started
+/// as another account do, is not a login.
+fn logged_in_since(before: &[(Account, Value)], after: &[(Account, Value)]) -> bool {
+    after.iter().any(|(account, entry)| {
+        !before
+            .iter()
+            .any(|(known, known_entry)| known.same_user(account) && known_entry == entry)
+    })
+}
+
+/// Follow a login until it ends, emitting the challenge once it's printed. Returns the
+/// final event, or `None` when the login was cancelled.
+fn watch_login(
+    mut child: Box<dyn portable_pty::Child + Send + Sync>,
+    output: mpsc::Receiver<Vec<u8>>,
+    cancel: &AtomicBool,
+    emit: &dyn Fn(LoginEvent),
+) -> Option<LoginEvent> {
+    let started = Instant::now();
+    let before = login_snapshot();
+    let mut text = String::new();
+    let mut challenged = false;
+
+    let finish = |child: &mut Box<dyn portable_pty::Child + Send + Sync>, event| {
+        let _ = child.kill();
+        let _ = child.wait();
+        event
+    };
+
+    loop {
+        while let Ok(chunk) = output.try_recv() {
+            text.push_str(&String::from_utf8_lossy(&strip_ansi_escapes::strip(&chunk)));
+            if text.len() > OUTPUT_LIMIT {
+                let mut cut = text.len() - OUTPUT_LIMIT;
+                while !text.is_char_boundary(cut) {
+                    cut += 1;
+                }
+                text.drain(..cut);
+            }
+        }
+        if !challenged {
+            if let Some(challenge) = parse_challenge(&text) {
+                challenged = true;
+                emit(LoginEvent::Challenge(challenge));
+            }
+        }
+
+        if cancel.load(Ordering::Relaxed) {
+            return finish(&mut child, None);
+        }
+        if logged_in_since(&before, &login_snapshot()) {
+            return finish(&mut child, Some(LoginEvent::Succeeded(check_auth_status())));
+        }
+        if let Ok(Some(status)) = child.try_wait() {
+            let auth = check_auth_status();
+            return Some(if status.success() && auth.authenticated {
+                LoginEvent::Succeeded(auth)
+            } else {
+                let reason = last_line(&text).unwrap_or("copilot login failed");
+                LoginEvent::Failed(reason.to_string())
+            });
+        }
+        if started.elapsed() >= LOGIN_TIMEOUT {
+            return finish(&mut child, Some(LoginEvent::TimedOut));
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// The one-time code and verification URL from `copilot login` output, e.g.
+/// "To authenticate, visit https://github.com/login/device and enter code 1A2B-3C4D."
+pub fn parse_challenge(text: &str) -> Option<LoginChallenge> {
+    let words: Vec<&str> = text
+        .split_whitespace()
+        .map(|w| {
+            w.trim_matches(|c: char| matches!(c, '.' | ',' | ':' | ';' | '"' | '\'' | '(' | ')'))
+        })
+        .collect();
+    let user_code = words.iter().find(|w| is_user_code(w))?;
+    let urls = words
+        .iter()
+        .filter(|w| w.starts_with("https://") || w.starts_with("http://"));
+    let verification_uri = urls
+        .clone()
+        .find(|w| w.contains("/login/device"))
+        .or_else(|| urls.clone().next())?;
+    Some(LoginChallenge {
+        user_code: user_code.to_string(),
+        verification_uri: verification_uri.to_string(),
+    })
+}
+
+/// GitHub device codes look like `1A2B-3C4D`
+fn is_user_code(word: &str) -> bool {
+    let Some((left, right)) = word.split_once('-') else {
+        return false;
+    };
+    [left, right].iter().all(|part| {
+        part.len() == 4
+            && part
+                .chars()
+                .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase())
+    })
+}
+
+fn last_line(text: &str) -> Option<&str> {
+    text.lines().rev().map(str::trim).find(|l| !l.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_challenge() {
+        let output = "Waiting for authorization...\r\n\
+            ! First copy your one-time code: 1A2B-3C4D\r\n\
+            Open https://github.com/login/device in your browser.\r\n";
+        assert_eq!(
+            parse_challenge(output),
+            Some(LoginChallenge {
+                user_code: "1A2B-3C4D".to_string(),
+                verification_uri: "https://github.com/login/device".to_string(),
+            })
+        );
+        let ghes = "Visit https://ghe.example.com/login/device and enter code WXYZ-0123.";
+        assert_eq!(
+            parse_challenge(ghes).unwrap().verification_uri,
+            "https://ghe.example.com/login/device"
+        );
+    }
+
+    #[test]
+    fn test_login_detection_ignores_account_switch() {
+        let config = serde_json::json!({
+            "last_logged_in_user": { "host": "https://github.com", "login": "octocat" },
+            "logged_in_users": [
+                { "host": "https://github.com", "login": "octocat" },
+                { "host": "https://ghe.example.com", "login": "octocat" }
+            ]
+        });
+        let before = login_entries(&config);
+
+        // A session started as the GHES account rewrites only `last_logged_in_user`
+        let mut switched = config.clone();
+        switched["last_logged_in_user"] = config["logged_in_users"][1].clone();
+        assert!(!logged_in_since(&before, &login_entries(&switched)));
+
+        let mut added = config.clone();
+        added["logged_in_users"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!({ "host": "https://github.com", "login": "hubot" }));
+        assert!(logged_in_since(&before, &login_entries(&added)));
+
+        let mut renewed = config.clone();
+        renewed["logged_in_users"][0]["token_ref"] = "keychain".into();
+        assert!(logged_in_since(&before, &login_entries(&renewed)));
+    }
+
+    #[test]
+    fn test_finished_login_cannot_be_cancelled() {
+        let manager = LoginManager::new();
+        let first = Arc::new(AtomicBool::new(false));
+        let second = Arc::new(AtomicBool::new(false));
+        *manager.active.lock().unwrap() = Some(second.clone());
+        // A replaced login ending leaves the current one alone
+        finish_login(&manager.active, &first);
+        assert!(manager.active.lock().unwrap().is_some());
+        finish_login(&manager.active, &second);
+        assert!(!manager.cancel());
+    }
+
+    #[test]
+    fn test_partial_output_has_no_challenge() {
+        assert_eq!(
+            parse_challenge("Open https://github.com/login/device"),
+            None
+        );
+        assert_eq!(parse_challenge("enter code 1A2B-3C"), None);
+        assert!(!is_user_code("gpt-5"));
+        assert!(!is_user_code("abcd-efgh"));
+    }
+}
@@ -1,43 +1,359 @@
+mod login;
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
 use serde_json::Value;
 
-use crate::types::AuthStatus;
+use crate::types::{Account, AppError, AuthStatus};
+
+pub use login::LoginManager;
+
+/// Host the CLI records for github.com accounts
+const GITHUB_HOST: &str = "https://github.com";
+
+/// Serializes our read-modify-write cycles on the CLI's config file
+static CONFIG_LOCK: Mutex<()> = Mutex::new(());
+
+/// Accounts the running sessions were started as. Taken before `CONFIG_LOCK`.
+static LEASES: Mutex<AccountLeases> = Mutex::new(AccountLeases {
+    sessions: BTreeMap::new(),
+    restore: None,
+});
+
+/// The CLI has no per-process account option: it signs in as the config's
+/// `last_logged_in_user`, read some time after it starts. So sessions only run as
+/// another account while no session needs a different one, and the user's own active
+/// account is put back once the last of them ends.
+#[derive(Debug, Clone, Default)]
+struct AccountLeases {
+    /// Account of each running session, by session id
+    sessions: BTreeMap<String, Account>,
+    /// The user's active account while the config points at a session's account
+    restore: Option<Account>,
+}
+
+impl AccountLeases {
+    /// Record `session_id` as running as `account`, or as the user's active account
+    /// for `None`, and return the account the config must be pointed at, if any.
+    /// A session that needs a different account than the others is refused.
+    fn acquire(
+        &mut self,
+        session_id: &str,
+        account: Option<&Account>,
+        active: Option<Account>,
+    ) -> Result<Option<Account>, AppError> {
+        let Some(wanted) = account.cloned().or_else(|| self.restore.clone()).or(active.clone())
+        else {
+            return Ok(None);
+        };
+        if let Some(other) = self
+            .sessions
+            .iter()
+            .find(|(id, held)| *id != session_id && !held.same_user(&wanted))
+            .map(|(_, held)| held)
+        {
+            return Err(AppError::Other(format!(
+                "Another session is running as {} on {}; close it before starting one as {} on {}",
+                other.login, other.host, wanted.login, wanted.host
+            )));
+        }
+        self.sessions.insert(session_id.to_string(), wanted.clone());
+        if active.as_ref().is_some_and(|active| active.same_user(&wanted)) {
+            return Ok(None);
+        }
+        if self.restore.is_none() {
+            self.restore = active;
+        } else if self.restore.as_ref().is_some_and(|r| r.same_user(&wanted)) {
+            self.restore = None;
+        }
+        Ok(Some(wanted))
+    }
+
+    /// Forget `session_id` and return the account to put back when it was the last
+    /// session running as another one
+    fn release(&mut self, session_id: &str) -> Option<Account> {
+        self.sessions.remove(session_id);
+        if self.sessions.is_empty() {
+            self.restore.take()
+        } else {
+            None
+        }
+    }
+}
+
+fn lock_leases() -> std::sync::MutexGuard<'static, AccountLeases> {
+    LEASES.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+fn copilot_config_path() -> Option<PathBuf> {
+    dirs::home_dir().map(|home| home.join(".copilot").join("config.json"))
+}
+
+fn read_config(path: &Path) -> Option<Value> {
+    let contents = std::fs::read_to_string(path).ok()?;
+    serde_json::from_str(&contents).ok()
+}
+
+fn parse_account(user: &Value) -> Option<Account> {
+    let login = user.get("login").and_then(|v| v.as_str())?;
+    Some(Account {
+        host: user
+            .get("host")
+            .and_then(|v| v.as_str())
+            .unwrap_or(GITHUB_HOST)
+            .to_string(),
+        login: login.to_string(),
+        email: user.get("email").and_then(|v| v.as_str()).map(|s| s.to_string()),
+    })
+}
+
+/// Every account in `logged_in_users`, in the CLI's order
+fn accounts_in(config: &Value) -> Vec<Account> {
+    let mut accounts: Vec<Account> = config
+        .get("logged_in_users")
+        .and_then(|v| v.as_array())
+        .map(|users| users.iter().filter_map(parse_account).collect())
+        .unwrap_or_default();
+    accounts.dedup_by(|a, b| a.same_user(b));
+    accounts
+}
+
+/// The account the CLI signs in with: `last_logged_in_user`, else the first logged-in user
+fn active_in(config: &Value) -> Option<Account> {
+    config
+        .get("last_logged_in_user")
+        .and_then(parse_account)
+        .or_else(|| accounts_in(config).into_iter().next())
+}
+
+/// Make `account` the CLI's `last_logged_in_user`, copying its `logged_in_users`
+/// entry so fields this app doesn't know survive.
+fn select_in(config: &mut Value, account: &Account) -> Result<(), AppError> {
+    let entry = config
+        .get("logged_in_users")
+        .and_then(|v| v.as_array())
+        .and_then(|users| {
+            users
+                .iter()
+                .find(|user| parse_account(user).is_some_and(|a| a.same_user(account)))
+        })
+        .cloned()
+        .ok_or_else(|| {
+            AppError::Other(format!(
+                "{} is not logged in on {}; run `copilot login` first",
+                account.login, account.host
+            ))
+        })?;
+    let object = config
+        .as_object_mut()
+        .ok_or_else(|| AppError::Other("Copilot config is not a JSON object".to_string()))?;
+    object.insert("last_logged_in_user".to_string(), entry);
+    Ok(())
+}
 
 /// Check if the user is authenticated with GitHub Copilot
 /// Reads ~/.copilot/config.json which contains logged_in_users array
+///
+/// While a session runs as another account, the user's own active account is reported.
 pub fn check_auth_status() -> AuthStatus {
-    let home = match dirs::home_dir() {
-        Some(h) => h,
-        None => return AuthStatus { authenticated: false, username: None, email: None },
-    };
-
-    // Primary: ~/.copilot/config.json — prefer last_logged_in_user, fall back to logged_in_users
-    let config_path = home.join(".copilot").join("config.json");
-    if let Ok(contents) = std::fs::read_to_string(&config_path) {
-        if let Ok(config) = serde_json::from_str::<Value>(&contents) {
-            // Prefer last_logged_in_user (the active user)
-            if let Some(last_user) = config.get("last_logged_in_user") {
-                let login = last_user.get("login").and_then(|v| v.as_str()).map(|s| s.to_string());
-                if login.is_some() {
-                    return AuthStatus {
-                        authenticated: true,
-                        username: login,
-                        email: None,
-                    };
-                }
-            }
-            // Fall back to first entry in logged_in_users
-            if let Some(users) = config.get("logged_in_users").and_then(|v| v.as_array()) {
-                if let Some(first_user) = users.first() {
-                    let login = first_user.get("login").and_then(|v| v.as_str()).map(|s| s.to_string());
-                    return AuthStatus {
-                        authenticated: true,
-                        username: login,
-                        email: None,
-                    };
-                }
-            }
-        }
-    }
-
-    AuthStatus { authenticated: false, username: None, email: None }
+    match active_account() {
+        Some(account) => AuthStatus {
+            authenticated: true,
+            username: Some(account.login),
+            email: account.email,
+            host: Some(account.host),
+        },
+        None => AuthStatus {
+            authenticated: false,
+            username: None,
+            email: None,
+            host: None,
+        },
+    }
+}
+
+/// All accounts the CLI has credentials for
+/// The user's active account, reported like `check_auth_status` does
+pub fn active_account() -> Option<Account> {
+    let restore = lock_leases().restore.clone();
+    restore.or_else(|| {
+        copilot_config_path()
+            .and_then(|path| read_config(&path))
+            .and_then(|config| active_in(&config))
+    })
+}
+
+pub fn list_accounts() -> Vec<Account> {
+    copilot_config_path()
+        .and_then(|path| read_config(&path))
+        .map(|config| accounts_in(&config))
+        .unwrap_or_default()
+}
+
+/// Point the CLI at `account` for the sessions it starts from now on. It becomes the
+/// user's active account, which sessions started as another account no longer revert.
+pub fn switch_account(account: &Account) -> Result<(), AppError> {
+    let mut leases = lock_leases();
+    write_active(account)?;
+    leases.restore = None;
+    Ok(())
+}
+
+/// Start `session_id` as `account`, or as the user's active account for `None`,
+/// pointing the CLI's config at it if needed. Fails while another session runs as a
+/// different account. Pair with `release_account` when the session ends.
+pub fn acquire_account(session_id: &str, account: Option<&Account>) -> Result<(), AppError> {
+    let mut leases = lock_leases();
+    let active = copilot_config_path()
+        .and_then(|path| read_config(&path))
+        .and_then(|config| active_in(&config));
+    let before = leases.clone();
+    if let Some(wanted) = leases.acquire(session_id, account, active)? {
+        // Leave the leases as they were if the config can't be pointed at it
+        if let Err(e) = write_active(&wanted) {
+            *leases = before;
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// Forget a session's account, putting the user's active account back when it was
+/// the last session running as another one
+pub fn release_account(session_id: &str) {
+    let mut leases = lock_leases();
+    if let Some(account) = leases.release(session_id) {
+        if let Err(e) = write_active(&account) {
+            log::warn!("Failed to restore the active Copilot account: {}", e);
+        }
+    }
+}
+
+/// Put the user's active account back on exit, whatever sessions are still running
+pub fn restore_active_account() {
+    let mut leases = lock_leases();
+    leases.sessions.clear();
+    if let Some(account) = leases.restore.take() {
+        if let Err(e) = write_active(&account) {
+            log::warn!("Failed to restore the active Copilot account: {}", e);
+        }
+    }
+}
+
+/// Make `account` the config's `last_logged_in_user`.
+///
+/// The file is rewritten through a temp file and a rename so the CLI never sees a
+/// half-written config, and keeps its permissions since it holds account data.
+fn write_active(account: &Account) -> Result<(), AppError> {
+    let _guard = CONFIG_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+    let path = copilot_config_path().ok_or_else(|| AppError::Other("No home dir".into()))?;
+    let contents = std::fs::read_to_string(&path)?;
+    let mut config: Value = serde_json::from_str(&contents)
+        .map_err(|e| AppError::Other(format!("Invalid Copilot config: {}", e)))?;
+    if active_in(&config).is_some_and(|active| active.same_user(account)) {
+        return Ok(());
+    }
+    select_in(&mut config, account)?;
+
+    let data = serde_json::to_string_pretty(&config)
+        .map_err(|e| AppError::Other(e.to_string()))?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::set_permissions(&tmp, std::fs::metadata(&path)?.permissions())?;
+    std::fs::rename(&tmp, &path)?;
+    Ok(())
+}
+
+/// Environment that points `gh`-style host lookups at an account's server
+pub fn host_env(host: &str) -> Vec<(String, String)> {
+    if host.trim_end_matches('/') == GITHUB_HOST {
+        return Vec::new();
+    }
+    let host = host
+        .trim_start_matches("https://")
+        .trim_start_matches("http://")
+        .trim_end_matches('/');
+    vec![("GH_HOST".to_string(), host.to_string())]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> Value {
+        serde_json::json!({
+            "banner": "never",
+            "last_logged_in_user": { "host": "https://github.com", "login": "octocat" },
+            "logged_in_users": [
+                { "host": "https://github.com", "login": "octocat" },
+                { "host": "https://ghe.example.com", "login": "octocat", "token_ref": "keychain" }
+            ]
+        })
+    }
+
+    fn enterprise() -> Account {
+        Account {
+            host: "https://ghe.example.com".to_string(),
+            login: "octocat".to_string(),
+            email: None,
+        }
+    }
+
+    #[test]
+    fn test_accounts_keep_host() {
+        let accounts = accounts_in(&config());
+        assert_eq!(accounts.len(), 2);
+        assert!(accounts[1].same_user(&enterprise()));
+        assert_eq!(active_in(&config()).unwrap().host, GITHUB_HOST);
+    }
+
+    #[test]
+    fn test_select_account_copies_entry() {
+        let mut config = config();
+        select_in(&mut config, &enterprise()).unwrap();
+        assert_eq!(config["last_logged_in_user"]["token_ref"], "keychain");
+        assert_eq!(config["banner"], "never");
+        assert!(active_in(&config).unwrap().same_user(&enterprise()));
+
+        let stranger = Account {
+            login: "hubot".to_string(),
+            ..enterprise()
+        };
+        assert!(select_in(&mut config, &stranger).is_err());
+    }
+
+    #[test]
+    fn test_sessions_share_one_account() {
+        let github = active_in(&config()).unwrap();
+        let mut leases = AccountLeases::default();
+
+        // The active account needs no switch, and any session may join it
+        assert!(leases.acquire("a", None, Some(github.clone())).unwrap().is_none());
+        assert!(leases.acquire("b", Some(&enterprise()), Some(github.clone())).is_err());
+        assert!(leases.release("a").is_none());
+
+        // Another account is switched to, and put back after its last session
+        let switched = leases.acquire("b", Some(&enterprise()), Some(github.clone()));
+        assert!(switched.unwrap().unwrap().same_user(&enterprise()));
+        let active = Some(enterprise());
+        assert!(leases.acquire("c", Some(&enterprise()), active.clone()).unwrap().is_none());
+        // A session without an account keeps the user's, which conflicts
+        assert!(leases.acquire("d", None, active.clone()).is_err());
+        // Restarting a session as another account only conflicts with the others
+        assert!(leases.acquire("c", Some(&github), active.clone()).is_err());
+        assert!(leases.release("b").is_none());
+        assert!(leases.release("c").unwrap().same_user(&github));
+        assert!(leases.restore.is_none());
+    }
+
+    #[test]
+    fn test_host_env() {
+        assert!(host_env(&active_in(&config()).unwrap().host).is_empty());
+        assert_eq!(
+            host_env(&enterprise().host),
+            vec![("GH_HOST".to_string(), "ghe.example.com".to_string())]
+        );
+    }
 }
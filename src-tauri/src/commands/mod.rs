@@ -1,46 +1,122 @@
-use tauri::{AppHandle, State};
-
-use crate::auth;
-use crate::config::ConfigManager;
-use crate::pty::PtyManager;
-use crate::session::SessionManager;
+use tauri::{AppHandle, Manager, State};
+
+use crate::auth::{self, LoginManager};
+use crate::config::{ConfigManager, SpawnProfile, ToolPolicy};
+use crate::pty::cli::{self, CopilotCli};
+use crate::pty::{spawn_args, PtyManager, SpawnOptions};
+use crate::session::events::{self, CopilotEvent, TimelinePage};
+use crate::session::{store, EventStreams, SessionManager};
 use crate::files::FileWatcher;
-use crate::types::{AgentMode, AppError, AuthStatus, CopilotSession, CopilotStatus, DiffResult, FileChangeEvent, McpServerConfig, ModelInfo, PluginInfo, SessionInfo, UsageMetrics};
+use crate::types::{Account, AgentMode, AppError, AuthStatus, CopilotSession, CopilotStatus, DiffResult, FileChangeEvent, KeyInput, McpImportCandidate, McpProbeResult, McpScope, McpServerConfig, MessageRole, ModelInfo, PluginInfo, SessionInfo, SessionMessage, SessionOutput, UsageMetrics, UsageSummary};
+use crate::usage::{SessionUsage, UsageTracker};
 use crate::mcp::McpManager;
+use crate::models::{self, ModelCatalog};
 use crate::plugins::PluginManager;
 
-#[tauri::command]
-pub fn check_copilot_status(pty: State<'_, PtyManager>) -> CopilotStatus {
-    let path = pty.get_copilot_path();
+fn copilot_status(pty: &PtyManager) -> CopilotStatus {
+    let cli = pty.copilot_cli();
+    let version = cli.as_ref().and_then(|c| c.version);
     CopilotStatus {
-        installed: pty.is_copilot_installed(),
-        path,
-        version: None,
-        authenticated: false,
+        installed: cli.is_some(),
+        path: cli.as_ref().map(|c| c.path.clone()),
+        version: version.map(|v| v.to_string()),
+        authenticated: auth::check_auth_status().authenticated,
+        supported: !matches!(&cli, Some(c) if !c.is_supported()),
+        unsupported_flags: cli
+            .map(|c| c.unsupported_features().iter().map(|f| f.flag().to_string()).collect())
+            .unwrap_or_default(),
+        error: pty.copilot_error(),
     }
 }
 
+#[tauri::command(async)]
+pub fn check_copilot_status(pty: State<'_, PtyManager>) -> Result<CopilotStatus, AppError> {
+    Ok(copilot_status(&pty))
+}
+
+/// The binary a `copilot_path` setting selects: the configured one, which must pass
+/// validation, or `copilot` on PATH when unset.
+fn select_copilot_cli(path: Option<&str>) -> Result<Option<CopilotCli>, AppError> {
+    match path {
+        Some(path) => cli::validate(path).map(Some),
+        None => cli::detect(None),
+    }
+}
+
+/// Use the Copilot CLI at `path` for new sessions, or `None` to go back to `copilot`
+/// on PATH. The binary must pass `cli::validate` before it replaces the current one.
+#[tauri::command(async)]
+pub fn set_copilot_path(
+    path: Option<String>,
+    pty: State<'_, PtyManager>,
+    config: State<'_, ConfigManager>,
+) -> Result<CopilotStatus, AppError> {
+    let path = path
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+    let new_cli = select_copilot_cli(path.as_deref())?;
+
+    let mut app_config = config.get_config();
+    app_config.copilot_path = path;
+    config.update_config(app_config)?;
+    pty.set_copilot_cli(Ok(new_cli));
+    Ok(copilot_status(&pty))
+}
+
+/// Start a session's CLI along with the readers that follow it: its event log and
+/// its working directory
+fn launch_session(
+    session_id: &str,
+    options: &SpawnOptions,
+    app_handle: &AppHandle,
+) -> Result<(), AppError> {
+    app_handle
+        .state::<PtyManager>()
+        .spawn_session(session_id, options, app_handle.clone())?;
+    app_handle
+        .state::<EventStreams>()
+        .start(session_id, app_handle.clone());
+    let _ = app_handle.state::<FileWatcher>().start_watching(
+        session_id,
+        &options.working_dir,
+        app_handle.clone(),
+    );
+    Ok(())
+}
+
+/// `launch_session` for a session that was just created. It is removed again when its
+/// CLI can't start, so a failed spawn isn't offered for restore on every launch.
+fn launch_new_session(
+    session_mgr: &SessionManager,
+    session_id: &str,
+    options: &SpawnOptions,
+    app_handle: &AppHandle,
+) -> Result<(), AppError> {
+    launch_session(session_id, options, app_handle).inspect_err(|_| {
+        session_mgr.remove_session(session_id);
+    })
+}
+
 #[tauri::command]
 pub fn create_session(
     name: &str,
     working_dir: &str,
     session_mgr: State<'_, SessionManager>,
-    pty: State<'_, PtyManager>,
     config: State<'_, ConfigManager>,
-    file_watcher: State<'_, FileWatcher>,
     app_handle: AppHandle,
 ) -> Result<SessionInfo, AppError> {
     let session = session_mgr.create_session(name, working_dir);
-    let model = config.get_config().default_model;
-    pty.spawn_session(
-        &session.id,
-        working_dir,
-        model.as_deref(),
-        Some("suggest"),
-        None,
-        app_handle.clone(),
-    )?;
-    let _ = file_watcher.start_watching(&session.id, working_dir, app_handle);
+    let app_config = config.get_config();
+    let options = SpawnOptions {
+        working_dir: working_dir.to_string(),
+        model: app_config.default_model.clone(),
+        mode: session.mode.clone(),
+        resume_session_id: None,
+        tool_policy: app_config.tool_policy_for(working_dir),
+        profile: app_config.spawn_profile_for(working_dir),
+        account: None,
+    };
+    launch_new_session(&session_mgr, &session.id, &options, &app_handle)?;
     config.add_recent_project(working_dir);
     Ok(session)
 }
@@ -54,6 +130,29 @@ pub fn send_message(
     pty.write_to_session(session_id, message)
 }
 
+#[tauri::command]
+pub fn send_keys(
+    session_id: &str,
+    keys: Vec<KeyInput>,
+    pty: State<'_, PtyManager>,
+) -> Result<(), AppError> {
+    pty.send_keys(session_id, &keys)
+}
+
+#[tauri::command]
+pub fn interrupt_session(session_id: &str, pty: State<'_, PtyManager>) -> Result<(), AppError> {
+    pty.interrupt_session(session_id)
+}
+
+#[tauri::command]
+pub fn respond_to_permission(
+    session_id: &str,
+    choice: usize,
+    pty: State<'_, PtyManager>,
+) -> Result<(), AppError> {
+    pty.respond_to_permission(session_id, choice)
+}
+
 #[tauri::command]
 pub fn resize_terminal(
     session_id: &str,
@@ -64,6 +163,24 @@ pub fn resize_terminal(
     pty.resize_pty(session_id, rows, cols)
 }
 
+#[tauri::command]
+pub fn get_session_output(
+    session_id: &str,
+    since_seq: Option<u64>,
+    pty: State<'_, PtyManager>,
+) -> Result<SessionOutput, AppError> {
+    pty.get_output(session_id, since_seq)
+}
+
+#[tauri::command]
+pub fn set_raw_output(
+    session_id: &str,
+    enabled: bool,
+    pty: State<'_, PtyManager>,
+) -> Result<(), AppError> {
+    pty.set_raw_output(session_id, enabled)
+}
+
 #[tauri::command]
 pub fn list_sessions(session_mgr: State<'_, SessionManager>) -> Vec<SessionInfo> {
     session_mgr.list_sessions()
@@ -75,9 +192,13 @@ pub fn close_session(
     session_mgr: State<'_, SessionManager>,
     pty: State<'_, PtyManager>,
     file_watcher: State<'_, FileWatcher>,
+    event_streams: State<'_, EventStreams>,
+    config: State<'_, ConfigManager>,
+    app_handle: AppHandle,
 ) -> Result<bool, AppError> {
-    pty.kill_session(session_id)?;
+    pty.shutdown_session(session_id, &config.get_config().shutdown, app_handle)?;
     file_watcher.stop_watching(session_id);
+    event_streams.stop(session_id);
     Ok(session_mgr.remove_session(session_id))
 }
 
@@ -101,14 +222,24 @@ pub fn check_auth() -> AuthStatus {
     auth::check_auth_status()
 }
 
+#[tauri::command]
+pub fn list_accounts() -> Vec<Account> {
+    auth::list_accounts()
+}
+
+/// Make `account` the CLI's active account for sessions without their own selection.
+#[tauri::command]
+pub fn switch_account(account: Account) -> Result<AuthStatus, AppError> {
+    auth::switch_account(&account)?;
+    Ok(auth::check_auth_status())
+}
+
 #[tauri::command]
 pub fn open_project(
     path: &str,
     name: Option<&str>,
     session_mgr: State<'_, SessionManager>,
-    pty: State<'_, PtyManager>,
     config: State<'_, ConfigManager>,
-    file_watcher: State<'_, FileWatcher>,
     app_handle: AppHandle,
 ) -> Result<SessionInfo, AppError> {
     let session_name = name.unwrap_or_else(|| {
@@ -118,16 +249,17 @@ pub fn open_project(
             .unwrap_or("project")
     });
     let session = session_mgr.create_session(session_name, path);
-    let model = config.get_config().default_model;
-    pty.spawn_session(
-        &session.id,
-        path,
-        model.as_deref(),
-        Some("suggest"),
-        None,
-        app_handle.clone(),
-    )?;
-    let _ = file_watcher.start_watching(&session.id, path, app_handle);
+    let app_config = config.get_config();
+    let options = SpawnOptions {
+        working_dir: path.to_string(),
+        model: app_config.default_model.clone(),
+        mode: session.mode.clone(),
+        resume_session_id: None,
+        tool_policy: app_config.tool_policy_for(path),
+        profile: app_config.spawn_profile_for(path),
+        account: None,
+    };
+    launch_new_session(&session_mgr, &session.id, &options, &app_handle)?;
     config.add_recent_project(path);
     Ok(session)
 }
@@ -137,14 +269,15 @@ pub fn get_recent_projects(config: State<'_, ConfigManager>) -> Vec<String> {
     config.get_config().recent_projects
 }
 
-#[tauri::command]
-pub fn detect_copilot_binary(pty: State<'_, PtyManager>) -> CopilotStatus {
-    CopilotStatus {
-        installed: pty.is_copilot_installed(),
-        path: pty.get_copilot_path(),
-        version: None,
-        authenticated: auth::check_auth_status().authenticated,
-    }
+/// Look for the Copilot CLI again, e.g. after the user installed it.
+#[tauri::command(async)]
+pub fn detect_copilot_binary(
+    pty: State<'_, PtyManager>,
+    config: State<'_, ConfigManager>,
+) -> Result<CopilotStatus, AppError> {
+    let configured = config.get_config().copilot_path;
+    pty.set_copilot_cli(cli::detect(configured.as_deref()));
+    Ok(copilot_status(&pty))
 }
 
 #[tauri::command]
@@ -165,14 +298,29 @@ pub fn get_diff(path: &str) -> Result<DiffResult, AppError> {
     FileWatcher::get_file_diff(path)
 }
 
+/// Plugins in the configured plugin directory, flagged when the configured registry
+/// has a newer version
 #[tauri::command]
-pub fn list_installed_plugins() -> Vec<PluginInfo> {
-    PluginManager::list_installed_plugins()
+pub async fn list_installed_plugins(
+    config: State<'_, ConfigManager>,
+) -> Result<Vec<PluginInfo>, AppError> {
+    let app_config = config.get_config();
+    let plugins_dir = PluginManager::plugins_dir(app_config.plugins_dir.as_deref());
+    Ok(
+        PluginManager::list_installed_plugins(app_config.plugin_registry.as_deref(), &plugins_dir)
+            .await,
+    )
 }
 
+/// Plugins offered by the configured registry index
 #[tauri::command]
-pub fn list_available_plugins() -> Vec<PluginInfo> {
-    PluginManager::list_available_plugins()
+pub async fn list_available_plugins(
+    config: State<'_, ConfigManager>,
+) -> Result<Vec<PluginInfo>, AppError> {
+    let app_config = config.get_config();
+    let plugins_dir = PluginManager::plugins_dir(app_config.plugins_dir.as_deref());
+    PluginManager::list_available_plugins(app_config.plugin_registry.as_deref(), &plugins_dir)
+        .await
 }
 
 #[tauri::command]
@@ -222,9 +370,10 @@ pub fn set_model(
     session_mgr: State<'_, SessionManager>,
     pty: State<'_, PtyManager>,
 ) -> Result<(), AppError> {
-    session_mgr.set_session_model(session_id, model);
     let command = format!("/model {}", model);
-    pty.write_to_session(session_id, &command)
+    pty.write_to_session(session_id, &command)?;
+    session_mgr.set_session_model(session_id, model);
+    Ok(())
 }
 
 #[tauri::command]
@@ -232,6 +381,9 @@ pub fn set_mode(
     session_id: &str,
     mode: &str,
     session_mgr: State<'_, SessionManager>,
+    pty: State<'_, PtyManager>,
+    config: State<'_, ConfigManager>,
+    app_handle: AppHandle,
 ) -> Result<(), AppError> {
     let agent_mode = match mode {
         "suggest" => AgentMode::Suggest,
@@ -239,48 +391,110 @@ pub fn set_mode(
         "autopilot" => AgentMode::Autopilot,
         _ => return Err(AppError::Other(format!("Unknown mode: {}", mode))),
     };
-    if session_mgr.set_session_mode(session_id, agent_mode) {
-        Ok(())
-    } else {
-        Err(AppError::SessionNotFound(session_id.to_string()))
+    let previous = session_mgr
+        .get_session(session_id)
+        .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+    if previous.mode == agent_mode {
+        return Ok(());
     }
+
+    // Tool permissions are fixed when the CLI starts, so a running session is
+    // restarted on the same Copilot conversation under the new profile. The mode is
+    // only saved once that worked.
+    if pty.has_session(session_id) {
+        session_mgr.link_copilot_sessions();
+        let mut session = session_mgr
+            .get_session(session_id)
+            .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+        if session.copilot_session_id.is_none() {
+            return Err(AppError::Other(
+                "The session's Copilot conversation isn't known yet, so changing the mode \
+                 would start a new one; send a message first"
+                    .to_string(),
+            ));
+        }
+        session.mode = agent_mode.clone();
+        let options = SpawnOptions::for_session(&session, &config.get_config());
+        pty.restart_session(session_id, &options, app_handle)?;
+    }
+    session_mgr.set_session_mode(session_id, agent_mode);
+    Ok(())
 }
 
+/// Switch a session to another logged-in account, restarting a running session on
+/// the same Copilot conversation so the CLI signs in again. `None` goes back to the
+/// CLI's active account.
 #[tauri::command]
-pub fn list_available_models() -> Vec<ModelInfo> {
-    vec![
-        ModelInfo { id: "claude-sonnet-4.5".into(), name: "Claude Sonnet 4.5".into(), provider: "Anthropic".into(), description: "Best balance of speed and intelligence".into() },
-        ModelInfo { id: "claude-haiku-4.5".into(), name: "Claude Haiku 4.5".into(), provider: "Anthropic".into(), description: "Fast and lightweight".into() },
-        ModelInfo { id: "claude-opus-4.6".into(), name: "Claude Opus 4.6".into(), provider: "Anthropic".into(), description: "Most capable Anthropic model".into() },
-        ModelInfo { id: "claude-opus-4.6-fast".into(), name: "Claude Opus 4.6 Fast".into(), provider: "Anthropic".into(), description: "Opus 4.6 with faster responses".into() },
-        ModelInfo { id: "claude-opus-4.5".into(), name: "Claude Opus 4.5".into(), provider: "Anthropic".into(), description: "Previous generation flagship".into() },
-        ModelInfo { id: "claude-sonnet-4".into(), name: "Claude Sonnet 4".into(), provider: "Anthropic".into(), description: "Fast and capable".into() },
-        ModelInfo { id: "gemini-3-pro-preview".into(), name: "Gemini 3 Pro".into(), provider: "Google".into(), description: "Preview of Google's latest".into() },
-        ModelInfo { id: "gpt-5.2-codex".into(), name: "GPT-5.2 Codex".into(), provider: "OpenAI".into(), description: "Latest codex model".into() },
-        ModelInfo { id: "gpt-5.2".into(), name: "GPT-5.2".into(), provider: "OpenAI".into(), description: "Latest GPT model".into() },
-        ModelInfo { id: "gpt-5.1-codex-max".into(), name: "GPT-5.1 Codex Max".into(), provider: "OpenAI".into(), description: "Maximum capability codex".into() },
-        ModelInfo { id: "gpt-5.1-codex".into(), name: "GPT-5.1 Codex".into(), provider: "OpenAI".into(), description: "Codex optimized".into() },
-        ModelInfo { id: "gpt-5.1".into(), name: "GPT-5.1".into(), provider: "OpenAI".into(), description: "GPT-5.1 generation".into() },
-        ModelInfo { id: "gpt-5".into(), name: "GPT-5".into(), provider: "OpenAI".into(), description: "OpenAI flagship".into() },
-        ModelInfo { id: "gpt-5.1-codex-mini".into(), name: "GPT-5.1 Codex Mini".into(), provider: "OpenAI".into(), description: "Small and fast codex".into() },
-        ModelInfo { id: "gpt-5-mini".into(), name: "GPT-5 Mini".into(), provider: "OpenAI".into(), description: "Small and efficient".into() },
-        ModelInfo { id: "gpt-4.1".into(), name: "GPT-4.1".into(), provider: "OpenAI".into(), description: "Previous generation".into() },
-    ]
-}
-
-#[tauri::command]
-pub fn get_default_model() -> String {
-    if let Some(home) = dirs::home_dir() {
-        let config_path = home.join(".copilot").join("config.json");
-        if let Ok(contents) = std::fs::read_to_string(&config_path) {
-            if let Ok(config) = serde_json::from_str::<serde_json::Value>(&contents) {
-                if let Some(model) = config.get("model").and_then(|v| v.as_str()) {
-                    return model.to_string();
-                }
-            }
+pub fn set_session_account(
+    session_id: &str,
+    account: Option<Account>,
+    session_mgr: State<'_, SessionManager>,
+    pty: State<'_, PtyManager>,
+    config: State<'_, ConfigManager>,
+    app_handle: AppHandle,
+) -> Result<(), AppError> {
+    if let Some(account) = &account {
+        if !auth::list_accounts().iter().any(|a| a.same_user(account)) {
+            return Err(AppError::Other(format!(
+                "{} is not logged in on {}",
+                account.login, account.host
+            )));
         }
     }
-    "claude-sonnet-4.5".to_string()
+
+    // The account is only saved once the running CLI was restarted as it
+    if pty.has_session(session_id) {
+        session_mgr.link_copilot_sessions();
+        let mut session = session_mgr
+            .get_session(session_id)
+            .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+        session.account = account.clone();
+        let options = SpawnOptions::for_session(&session, &config.get_config());
+        pty.restart_session(session_id, &options, app_handle)?;
+    }
+    if !session_mgr.set_session_account(session_id, account) {
+        return Err(AppError::SessionNotFound(session_id.to_string()));
+    }
+    Ok(())
+}
+
+/// Models offered by the installed CLI. The list is cached per CLI version for a day;
+/// `refresh` probes the CLI again.
+#[tauri::command(async)]
+pub fn list_available_models(
+    refresh: Option<bool>,
+    pty: State<'_, PtyManager>,
+    catalog: State<'_, ModelCatalog>,
+) -> Result<Vec<ModelInfo>, AppError> {
+    let account = auth::active_account();
+    Ok(catalog.list(
+        pty.copilot_cli().as_ref(),
+        account.as_ref(),
+        refresh.unwrap_or(false),
+    ))
+}
+
+/// The CLI's configured model, else the app's default model, else the first model the
+/// CLI offers.
+#[tauri::command(async)]
+pub fn get_default_model(
+    pty: State<'_, PtyManager>,
+    config: State<'_, ConfigManager>,
+    catalog: State<'_, ModelCatalog>,
+) -> Result<String, AppError> {
+    if let Some(model) = models::cli_default_model() {
+        return Ok(model);
+    }
+    if let Some(model) = config.get_config().default_model {
+        return Ok(model);
+    }
+    let account = auth::active_account();
+    catalog
+        .list(pty.copilot_cli().as_ref(), account.as_ref(), false)
+        .into_iter()
+        .find(|m| m.available != Some(false))
+        .map(|m| m.id)
+        .ok_or_else(|| AppError::Other("No models available".to_string()))
 }
 
 #[tauri::command]
@@ -307,58 +521,187 @@ pub fn get_session(
         .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))
 }
 
+/// MCP servers for a session in `working_dir`: the global config with the project's
+/// `.copilot/mcp-config.json` layered over it. Without a directory, only global ones.
 #[tauri::command]
-pub fn list_mcp_servers(mcp: State<'_, McpManager>) -> Result<Vec<McpServerConfig>, AppError> {
-    mcp.list_servers()
+pub fn list_mcp_servers(
+    working_dir: Option<&str>,
+    mcp: State<'_, McpManager>,
+) -> Result<Vec<McpServerConfig>, AppError> {
+    mcp.list_servers(working_dir)
 }
 
 #[tauri::command]
-pub fn add_mcp_server(config: McpServerConfig, mcp: State<'_, McpManager>) -> Result<(), AppError> {
-    mcp.add_server(config)
+pub fn add_mcp_server(
+    config: McpServerConfig,
+    working_dir: Option<&str>,
+    mcp: State<'_, McpManager>,
+) -> Result<(), AppError> {
+    mcp.add_server(config, working_dir)
 }
 
 #[tauri::command]
-pub fn update_mcp_server(name: &str, config: McpServerConfig, mcp: State<'_, McpManager>) -> Result<(), AppError> {
-    mcp.update_server(name, config)
+pub fn update_mcp_server(
+    name: &str,
+    config: McpServerConfig,
+    working_dir: Option<&str>,
+    mcp: State<'_, McpManager>,
+) -> Result<(), AppError> {
+    mcp.update_server(name, config, working_dir)
 }
 
 #[tauri::command]
-pub fn delete_mcp_server(name: &str, mcp: State<'_, McpManager>) -> Result<(), AppError> {
-    mcp.delete_server(name)
+pub fn delete_mcp_server(
+    name: &str,
+    working_dir: Option<&str>,
+    mcp: State<'_, McpManager>,
+) -> Result<(), AppError> {
+    mcp.delete_server(name, working_dir)
+}
+
+#[tauri::command]
+pub fn toggle_mcp_server(
+    name: &str,
+    enabled: bool,
+    working_dir: Option<&str>,
+    mcp: State<'_, McpManager>,
+) -> Result<(), AppError> {
+    mcp.toggle_server(name, enabled, working_dir)
 }
 
+/// Start a local MCP server, run the `initialize` handshake and list its tools,
+/// resources and prompts. The outcome becomes the server's `status`.
+#[tauri::command(async)]
+pub fn probe_mcp_server(
+    name: String,
+    working_dir: Option<String>,
+    mcp: State<'_, McpManager>,
+) -> Result<McpProbeResult, AppError> {
+    mcp.probe_server(&name, working_dir.as_deref())
+}
+
+/// MCP servers configured in VS Code, Claude Desktop and Cursor, ready to import
 #[tauri::command]
-pub fn toggle_mcp_server(name: &str, enabled: bool, mcp: State<'_, McpManager>) -> Result<(), AppError> {
-    mcp.toggle_server(name, enabled)
+pub fn preview_mcp_import(
+    working_dir: Option<&str>,
+    mcp: State<'_, McpManager>,
+) -> Result<Vec<McpImportCandidate>, AppError> {
+    mcp.preview_import(working_dir)
 }
 
+/// Add `servers` to the config of `scope` in a single write. Existing servers of the
+/// same name are an error unless `replace`.
 #[tauri::command]
+pub fn import_mcp_servers(
+    servers: Vec<McpServerConfig>,
+    scope: McpScope,
+    replace: bool,
+    working_dir: Option<&str>,
+    mcp: State<'_, McpManager>,
+) -> Result<(), AppError> {
+    mcp.import_servers(servers, scope, replace, working_dir)
+}
+
+#[tauri::command(async)]
 pub fn update_config(
     config: crate::config::AppConfig,
     config_mgr: State<'_, ConfigManager>,
+    pty: State<'_, PtyManager>,
 ) -> Result<(), AppError> {
+    config.validate().map_err(AppError::Other)?;
+    if config.copilot_path != config_mgr.get_config().copilot_path {
+        pty.set_copilot_cli(Ok(select_copilot_cli(config.copilot_path.as_deref())?));
+    }
     config_mgr
         .update_config(config)
         .map_err(|e| AppError::Io(e))
 }
 
+#[tauri::command]
+pub fn set_project_tool_policy(
+    project_path: &str,
+    policy: Option<ToolPolicy>,
+    config: State<'_, ConfigManager>,
+) -> Result<(), AppError> {
+    if let Some(policy) = &policy {
+        policy.validate().map_err(AppError::Other)?;
+    }
+    config
+        .set_project_policy(project_path, policy)
+        .map_err(AppError::Io)
+}
+
+#[tauri::command]
+pub fn set_project_spawn_profile(
+    project_path: &str,
+    profile: Option<SpawnProfile>,
+    config: State<'_, ConfigManager>,
+) -> Result<(), AppError> {
+    if let Some(profile) = &profile {
+        profile.validate().map_err(AppError::Other)?;
+    }
+    config
+        .set_project_profile(project_path, profile)
+        .map_err(AppError::Io)
+}
+
+/// The arguments the Copilot CLI would be (re)started with for a session. At spawn
+/// time `--additional-mcp-config` is added as well when the session has MCP servers
+/// to hand over, pointing at a file written for that process.
+#[tauri::command]
+pub fn preview_session_args(
+    session_id: &str,
+    session_mgr: State<'_, SessionManager>,
+    config: State<'_, ConfigManager>,
+) -> Result<Vec<String>, AppError> {
+    let session = session_mgr
+        .get_session(session_id)
+        .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+    Ok(spawn_args(&SpawnOptions::for_session(&session, &config.get_config())))
+}
+
 #[tauri::command]
 pub fn get_usage_metrics(
     session_id: &str,
     session_mgr: State<'_, SessionManager>,
+    config: State<'_, ConfigManager>,
+    usage: State<'_, UsageTracker>,
 ) -> Result<UsageMetrics, AppError> {
+    session_mgr.link_copilot_sessions();
     let session = session_mgr
         .get_session(session_id)
         .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+    let session_usage = session
+        .copilot_session_id
+        .as_deref()
+        .and_then(store::copilot_events_path)
+        .map(|path| SessionUsage::from_events(&events::read_events(&path)))
+        .unwrap_or_default();
+    let app_config = config.get_config();
+
     Ok(UsageMetrics {
-        premium_requests_used: 0,
-        premium_requests_limit: Some(300),
-        session_messages: 0,
-        session_tokens: None,
-        active_model: session.model.unwrap_or_else(|| "claude-sonnet-4.5".to_string()),
+        premium_requests_used: usage.premium_requests_this_month(),
+        premium_requests_limit: app_config.usage.premium_request_limit,
+        session_messages: session_usage.messages,
+        session_tokens: session_usage.tokens,
+        active_model: session_usage
+            .model
+            .or(session.model)
+            .or(app_config.default_model)
+            .unwrap_or_default(),
     })
 }
 
+/// Usage over the last `days` days grouped by day, project and model.
+#[tauri::command]
+pub fn get_usage_history(
+    days: u32,
+    config: State<'_, ConfigManager>,
+    usage: State<'_, UsageTracker>,
+) -> UsageSummary {
+    usage.summary(days, config.get_config().usage.premium_request_limit)
+}
+
 #[tauri::command]
 pub fn clear_session_history(
     session_id: &str,
@@ -367,47 +710,24 @@ pub fn clear_session_history(
     pty.write_to_session(session_id, "/clear")
 }
 
+/// Run `copilot login` and report the device code and the outcome on `login-event`.
+/// `host` signs in to a GitHub Enterprise Server instead of github.com.
 #[tauri::command]
-pub fn trigger_login(pty: State<'_, PtyManager>) -> Result<(), AppError> {
+pub fn trigger_login(
+    host: Option<String>,
+    pty: State<'_, PtyManager>,
+    login: State<'_, LoginManager>,
+    app_handle: AppHandle,
+) -> Result<(), AppError> {
     let copilot_path = pty
         .get_copilot_path()
         .ok_or(AppError::CopilotNotFound)?;
-    std::process::Command::new(&copilot_path)
-        .arg("login")
-        .spawn()
-        .map_err(|e| AppError::PtyError(e.to_string()))?;
-    Ok(())
+    login.start(&copilot_path, host.as_deref(), app_handle)
 }
 
-fn parse_workspace_yaml(content: &str) -> Option<CopilotSession> {
-    let mut id = String::new();
-    let mut cwd = String::new();
-    let mut summary = None;
-    let mut repository = None;
-    let mut branch = None;
-    let mut created_at = String::new();
-    let mut updated_at = String::new();
-
-    for line in content.lines() {
-        if let Some((key, value)) = line.split_once(": ") {
-            let value = value.trim();
-            match key.trim() {
-                "id" => id = value.to_string(),
-                "cwd" => cwd = value.to_string(),
-                "summary" => summary = Some(value.to_string()),
-                "repository" => repository = Some(value.to_string()),
-                "branch" => branch = Some(value.to_string()),
-                "created_at" => created_at = value.to_string(),
-                "updated_at" => updated_at = value.to_string(),
-                _ => {}
-            }
-        }
-    }
-
-    if id.is_empty() || cwd.is_empty() {
-        return None;
-    }
-    Some(CopilotSession { id, cwd, summary, repository, branch, created_at, updated_at })
+#[tauri::command]
+pub fn cancel_login(login: State<'_, LoginManager>) -> bool {
+    login.cancel()
 }
 
 #[tauri::command]
@@ -427,7 +747,7 @@ pub fn list_copilot_sessions() -> Vec<CopilotSession> {
             let entry = entry.ok()?;
             let path = entry.path().join("workspace.yaml");
             let content = std::fs::read_to_string(&path).ok()?;
-            parse_workspace_yaml(&content)
+            store::parse_workspace_yaml(&content)
         })
         .collect();
 
@@ -437,52 +757,61 @@ pub fn list_copilot_sessions() -> Vec<CopilotSession> {
 }
 
 #[tauri::command]
-pub fn get_session_events(session_id: &str) -> Vec<serde_json::Value> {
-    let home = match dirs::home_dir() {
-        Some(h) => h,
-        None => return Vec::new(),
-    };
-    let events_path = home
-        .join(".copilot")
-        .join("session-state")
-        .join(session_id)
-        .join("events.jsonl");
-    let content = match std::fs::read_to_string(&events_path) {
-        Ok(c) => c,
-        Err(_) => return Vec::new(),
+pub fn get_session_events(session_id: &str) -> Vec<SessionMessage> {
+    let Some(path) = store::copilot_events_path(session_id) else {
+        return Vec::new();
     };
 
-    content
-        .lines()
-        .filter_map(|line| {
-            let val: serde_json::Value = serde_json::from_str(line).ok()?;
-            let event_type = val.get("type")?.as_str()?;
-            match event_type {
-                "user.message" | "assistant.message" => {
-                    let data_content = val.get("data")?.get("content")?.as_str()?.to_string();
-                    if data_content.is_empty() {
-                        return None;
-                    }
-                    let id = val.get("id")?.as_str()?.to_string();
-                    let timestamp = val.get("timestamp")?.as_str()?.to_string();
-                    let role = if event_type == "user.message" {
-                        "user"
-                    } else {
-                        "assistant"
-                    };
-                    Some(serde_json::json!({
-                        "id": id,
-                        "role": role,
-                        "content": data_content,
-                        "timestamp": timestamp
-                    }))
-                }
-                _ => None,
+    events::read_events(&path)
+        .into_iter()
+        .filter_map(|envelope| {
+            let (role, content) = match envelope.event {
+                CopilotEvent::UserMessage(data) => (MessageRole::User, data.content),
+                CopilotEvent::AssistantMessage(data) => (MessageRole::Assistant, data.content),
+                _ => return None,
+            };
+            if content.is_empty() {
+                return None;
             }
+            Some(SessionMessage {
+                id: envelope.id,
+                role,
+                content,
+                timestamp: envelope.timestamp,
+            })
         })
         .collect()
 }
 
+/// A page of the full event history of a session. `session_id` may be a desktop
+/// session (its linked Copilot CLI session is used) or a Copilot CLI session id.
+/// `kinds` filters by event type (`tool.execution_start`) or namespace (`tool`).
+#[tauri::command]
+pub fn get_session_timeline(
+    session_id: &str,
+    offset: usize,
+    limit: usize,
+    kinds: Option<Vec<String>>,
+    session_mgr: State<'_, SessionManager>,
+) -> Result<TimelinePage, AppError> {
+    session_mgr.link_copilot_sessions();
+    let copilot_session_id = match session_mgr.get_session(session_id) {
+        Some(session) => session.copilot_session_id.ok_or_else(|| {
+            AppError::Other(format!("No Copilot CLI session linked to {}", session_id))
+        })?,
+        None => session_id.to_string(),
+    };
+    let path = store::copilot_events_path(&copilot_session_id)
+        .ok_or_else(|| AppError::Other("No home dir".into()))?;
+
+    Ok(events::timeline_page(
+        events::read_events(&path),
+        offset,
+        limit,
+        &kinds.unwrap_or_default(),
+    ))
+}
+
 #[tauri::command]
 pub fn get_copilot_config() -> Result<serde_json::Value, AppError> {
     let home = dirs::home_dir().ok_or(AppError::Other("No home dir".into()))?;
@@ -497,9 +826,7 @@ pub fn get_copilot_config() -> Result<serde_json::Value, AppError> {
 pub fn resume_session(
     session_id: &str,
     session_mgr: State<'_, SessionManager>,
-    pty: State<'_, PtyManager>,
     config: State<'_, ConfigManager>,
-    file_watcher: State<'_, FileWatcher>,
     app_handle: AppHandle,
 ) -> Result<SessionInfo, AppError> {
     // Read workspace.yaml from the copilot session to get cwd
@@ -511,7 +838,7 @@ pub fn resume_session(
         .join("workspace.yaml");
     let content = std::fs::read_to_string(&workspace_path)
         .map_err(|e| AppError::Io(e))?;
-    let copilot_session = parse_workspace_yaml(&content)
+    let copilot_session = store::parse_workspace_yaml(&content)
         .ok_or(AppError::Other("Failed to parse workspace.yaml".into()))?;
 
     let session_name = copilot_session
@@ -523,17 +850,61 @@ pub fn resume_session(
                 .and_then(|n| n.to_str())
                 .unwrap_or("session")
         });
-    let session = session_mgr.create_session(session_name, &copilot_session.cwd);
-    let model = config.get_config().default_model;
-    pty.spawn_session(
-        &session.id,
-        &copilot_session.cwd,
-        model.as_deref(),
-        Some("suggest"),
-        Some(session_id),
-        app_handle.clone(),
-    )?;
-    let _ = file_watcher.start_watching(&session.id, &copilot_session.cwd, app_handle);
+    let mut session = session_mgr.create_session(session_name, &copilot_session.cwd);
+    session_mgr.set_copilot_session_id(&session.id, session_id);
+    session.copilot_session_id = Some(session_id.to_string());
+    let app_config = config.get_config();
+    let options = SpawnOptions {
+        working_dir: copilot_session.cwd.clone(),
+        model: app_config.default_model.clone(),
+        mode: session.mode.clone(),
+        resume_session_id: Some(session_id.to_string()),
+        tool_policy: app_config.tool_policy_for(&copilot_session.cwd),
+        profile: app_config.spawn_profile_for(&copilot_session.cwd),
+        account: None,
+    };
+    launch_new_session(&session_mgr, &session.id, &options, &app_handle)?;
     config.add_recent_project(&copilot_session.cwd);
     Ok(session)
 }
+
+/// Desktop sessions restored from disk whose Copilot process is not running yet
+#[tauri::command]
+pub fn list_restorable_sessions(
+    session_mgr: State<'_, SessionManager>,
+    pty: State<'_, PtyManager>,
+) -> Vec<SessionInfo> {
+    session_mgr.link_copilot_sessions();
+    session_mgr
+        .list_sessions()
+        .into_iter()
+        .filter(|s| !pty.has_session(&s.id))
+        .collect()
+}
+
+/// Re-spawn the Copilot process for a persisted desktop session, resuming the
+/// linked Copilot CLI session when one is known.
+#[tauri::command]
+pub fn restore_session(
+    session_id: &str,
+    session_mgr: State<'_, SessionManager>,
+    pty: State<'_, PtyManager>,
+    config: State<'_, ConfigManager>,
+    app_handle: AppHandle,
+) -> Result<SessionInfo, AppError> {
+    if pty.has_session(session_id) {
+        return Err(AppError::Other(format!("Session already running: {}", session_id)));
+    }
+    session_mgr.link_copilot_sessions();
+    let session = session_mgr
+        .get_session(session_id)
+        .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+
+    let options = SpawnOptions::for_session(&session, &config.get_config());
+    launch_session(&session.id, &options, &app_handle)?;
+    session_mgr.set_crash_report(&session.id, None);
+    session_mgr.set_active_session(&session.id);
+    session_mgr
+        .get_session(session_id)
+        .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))
+}
@@ -0,0 +1,58 @@
+/// Parse a dotenv-style file: `KEY=value` lines, optionally prefixed with `export`.
+/// Blank lines and `#` comments are skipped; matching single or double quotes
+/// around a value are removed.
+pub fn parse_env_file(content: &str) -> Vec<(String, String)> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let key = key.trim();
+            if key.is_empty() {
+                return None;
+            }
+            Some((key.to_string(), unquote(value.trim()).to_string()))
+        })
+        .collect()
+}
+
+fn unquote(value: &str) -> &str {
+    for quote in ['"', '\''] {
+        if let Some(inner) = value
+            .strip_prefix(quote)
+            .and_then(|v| v.strip_suffix(quote))
+        {
+            return inner;
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_env_file() {
+        let content = "# proxy settings\n\
+                       HTTPS_PROXY=http://proxy:8080\n\
+                       \n\
+                       export GITHUB_TOKEN=\"ghp abc\"\n\
+                       NO_PROXY='localhost'\n\
+                       not a variable\n\
+                       EMPTY=\n";
+        assert_eq!(
+            parse_env_file(content),
+            vec![
+                ("HTTPS_PROXY".to_string(), "http://proxy:8080".to_string()),
+                ("GITHUB_TOKEN".to_string(), "ghp abc".to_string()),
+                ("NO_PROXY".to_string(), "localhost".to_string()),
+                ("EMPTY".to_string(), String::new()),
+            ]
+        );
+    }
+}
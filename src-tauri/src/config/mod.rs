@@ -1,5 +1,309 @@
+mod env_file;
+
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
+use std::time::Duration;
+
+/// Fine-grained tool permissions passed to the Copilot CLI as `--allow-tool`/`--deny-tool`.
+///
+/// Rules use the CLI's tool spec syntax: a tool kind optionally followed by a pattern,
+/// e.g. `shell(git status)`, `shell(rm *)`, `write(src/**)` or an MCP server name.
+/// Deny rules always win over allow rules.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct ToolPolicy {
+    #[serde(default)]
+    pub allow: Vec<String>,
+    #[serde(default)]
+    pub deny: Vec<String>,
+    /// Let tools touch paths outside the project and its `--add-dir` roots (`--allow-all-paths`)
+    #[serde(default)]
+    pub allow_all_paths: bool,
+}
+
+impl ToolPolicy {
+    pub fn validate(&self) -> Result<(), String> {
+        for rule in self.allow.iter().chain(&self.deny) {
+            validate_tool_rule(rule)?;
+        }
+        Ok(())
+    }
+
+    /// Layer `other` on top of this policy: rules are combined, deny still wins.
+    pub fn merged_with(&self, other: &ToolPolicy) -> ToolPolicy {
+        let mut merged = self.clone();
+        for rule in &other.allow {
+            if !merged.allow.contains(rule) {
+                merged.allow.push(rule.clone());
+            }
+        }
+        for rule in &other.deny {
+            if !merged.deny.contains(rule) {
+                merged.deny.push(rule.clone());
+            }
+        }
+        merged.allow_all_paths = self.allow_all_paths || other.allow_all_paths;
+        merged
+    }
+}
+
+/// Check a rule has the form `kind` or `kind(pattern)`.
+fn validate_tool_rule(rule: &str) -> Result<(), String> {
+    let rule = rule.trim();
+    let kind = match rule.split_once('(') {
+        Some((kind, rest)) => {
+            let pattern = rest
+                .strip_suffix(')')
+                .ok_or_else(|| format!("Unbalanced parentheses in tool rule: {}", rule))?;
+            if pattern.trim().is_empty() {
+                return Err(format!("Empty pattern in tool rule: {}", rule));
+            }
+            kind
+        }
+        None => rule,
+    };
+    if kind.is_empty()
+        || !kind
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+    {
+        return Err(format!("Invalid tool name in rule: {}", rule));
+    }
+    Ok(())
+}
+
+/// Environment and extra arguments the Copilot CLI is spawned with, globally or for
+/// the sessions in one project.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct SpawnProfile {
+    /// Extra environment variables, e.g. `HTTPS_PROXY` or `GH_HOST` for GitHub Enterprise Server
+    #[serde(default)]
+    pub env: HashMap<String, String>,
+    /// Directories put in front of `PATH`, for tools the agent should find
+    #[serde(default)]
+    pub path_prepend: Vec<String>,
+    /// `KEY=value` file read at spawn time, so secrets stay out of `config.json`.
+    /// Relative paths are resolved against the session's working directory.
+    #[serde(default)]
+    pub env_file: Option<String>,
+    /// Additional `--add-dir` roots besides the working directory
+    #[serde(default)]
+    pub add_dirs: Vec<String>,
+    /// Extra CLI flags appended after the ones the app manages
+    #[serde(default)]
+    pub extra_args: Vec<String>,
+}
+
+/// Flags derived from session state, tool policy and MCP servers; setting them in
+/// `extra_args` would conflict with what the app passes.
+const MANAGED_FLAGS: &[&str] = &[
+    "--resume",
+    "--model",
+    "--add-dir",
+    "--allow-tool",
+    "--deny-tool",
+    "--allow-all-tools",
+    "--allow-all-paths",
+    "--additional-mcp-config",
+];
+
+impl SpawnProfile {
+    pub fn validate(&self) -> Result<(), String> {
+        for key in self.env.keys() {
+            if key.is_empty() || key.contains(['=', '\0']) {
+                return Err(format!("Invalid environment variable name: {:?}", key));
+            }
+        }
+        for arg in &self.extra_args {
+            let flag = arg.split('=').next().unwrap_or_default();
+            if MANAGED_FLAGS.contains(&flag) {
+                return Err(format!(
+                    "{} is managed by the app and can't be set as an extra argument",
+                    flag
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Layer a project profile over this one: project env vars win and a project env
+    /// file replaces the global one. The project's `path_prepend` entries come first so
+    /// its tools are found first; its `add_dirs` and `extra_args` follow the global
+    /// ones, so a flag the project repeats is the one the CLI sees last.
+    pub fn merged_with(&self, other: &SpawnProfile) -> SpawnProfile {
+        let mut env = self.env.clone();
+        env.extend(other.env.clone());
+        SpawnProfile {
+            env,
+            path_prepend: combine(&other.path_prepend, &self.path_prepend),
+            env_file: other.env_file.clone().or_else(|| self.env_file.clone()),
+            add_dirs: combine(&self.add_dirs, &other.add_dirs),
+            extra_args: [self.extra_args.clone(), other.extra_args.clone()].concat(),
+        }
+    }
+
+    /// Environment variables for a session in `working_dir`: the env file first, then
+    /// `env`, with `PATH` extended by `path_prepend`.
+    pub fn resolve_env(
+        &self,
+        working_dir: &str,
+    ) -> Result<Vec<(String, String)>, std::io::Error> {
+        let mut vars = Vec::new();
+        if let Some(file) = &self.env_file {
+            let path = resolve_path(file, working_dir);
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                std::io::Error::new(e.kind(), format!("{}: {}", path.display(), e))
+            })?;
+            vars.extend(env_file::parse_env_file(&content));
+        }
+        vars.extend(self.env.iter().map(|(k, v)| (k.clone(), v.clone())));
+
+        if !self.path_prepend.is_empty() {
+            let current = vars
+                .iter()
+                .rev()
+                .find(|(k, _)| k == "PATH")
+                .map(|(_, v)| v.clone())
+                .or_else(|| std::env::var("PATH").ok())
+                .unwrap_or_default();
+            let dirs = self
+                .path_prepend
+                .iter()
+                .map(|dir| resolve_path(dir, working_dir))
+                .chain(std::env::split_paths(&current));
+            let path = std::env::join_paths(dirs).map_err(std::io::Error::other)?;
+            vars.push(("PATH".to_string(), path.to_string_lossy().into_owned()));
+        }
+        Ok(vars)
+    }
+}
+
+/// `a` followed by the entries of `b` not already in it
+fn combine(a: &[String], b: &[String]) -> Vec<String> {
+    let mut out = a.to_vec();
+    out.extend(b.iter().filter(|item| !a.contains(item)).cloned());
+    out
+}
+
+/// Expand a leading `~/` and resolve relative paths against `base`.
+fn resolve_path(path: &str, base: &str) -> PathBuf {
+    let expanded = match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    };
+    Path::new(base).join(expanded)
+}
+
+/// How long `close_session` waits at each stage before escalating:
+/// `/exit` → SIGTERM → kill.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ShutdownTimeouts {
+    /// Time the CLI gets to exit after `/exit` so it can save its session state
+    #[serde(default = "default_exit_timeout_ms")]
+    pub exit_timeout_ms: u64,
+    /// Time between SIGTERM and a hard kill
+    #[serde(default = "default_term_timeout_ms")]
+    pub term_timeout_ms: u64,
+}
+
+impl ShutdownTimeouts {
+    pub fn exit_timeout(&self) -> Duration {
+        Duration::from_millis(self.exit_timeout_ms)
+    }
+
+    pub fn term_timeout(&self) -> Duration {
+        Duration::from_millis(self.term_timeout_ms)
+    }
+}
+
+impl Default for ShutdownTimeouts {
+    fn default() -> Self {
+        Self {
+            exit_timeout_ms: default_exit_timeout_ms(),
+            term_timeout_ms: default_term_timeout_ms(),
+        }
+    }
+}
+
+fn default_exit_timeout_ms() -> u64 { 3000 }
+fn default_term_timeout_ms() -> u64 { 2000 }
+
+/// Automatic restart of a Copilot process that exits unexpectedly.
+/// The delay doubles with each consecutive crash, up to `max_backoff_ms`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RestartPolicy {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Consecutive crashes after which the session is left stopped
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+    #[serde(default = "default_initial_backoff_ms")]
+    pub initial_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// A process that ran at least this long resets the crash count
+    #[serde(default = "default_stable_after_ms")]
+    pub stable_after_ms: u64,
+}
+
+impl RestartPolicy {
+    /// Delay before restart number `attempt` (0-based), or `None` when no restart should happen.
+    pub fn backoff(&self, attempt: u32) -> Option<Duration> {
+        if !self.enabled || attempt >= self.max_restarts {
+            return None;
+        }
+        let delay = self
+            .initial_backoff_ms
+            .saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX))
+            .min(self.max_backoff_ms);
+        Some(Duration::from_millis(delay))
+    }
+
+    pub fn stable_after(&self) -> Duration {
+        Duration::from_millis(self.stable_after_ms)
+    }
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            max_restarts: default_max_restarts(),
+            initial_backoff_ms: default_initial_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            stable_after_ms: default_stable_after_ms(),
+        }
+    }
+}
+
+fn default_max_restarts() -> u32 { 5 }
+fn default_initial_backoff_ms() -> u64 { 1000 }
+fn default_max_backoff_ms() -> u64 { 30_000 }
+fn default_stable_after_ms() -> u64 { 60_000 }
+
+/// Premium request quota tracking. The Copilot CLI doesn't report the plan's quota,
+/// so warnings only happen once the user sets `premium_request_limit`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct UsageSettings {
+    /// Premium requests included in the plan per calendar month
+    #[serde(default)]
+    pub premium_request_limit: Option<u32>,
+    /// Share of the limit, in percent, at which a `usage-warning` event is emitted
+    #[serde(default = "default_warn_at_percent")]
+    pub warn_at_percent: u8,
+}
+
+impl Default for UsageSettings {
+    fn default() -> Self {
+        Self {
+            premium_request_limit: None,
+            warn_at_percent: default_warn_at_percent(),
+        }
+    }
+}
+
+fn default_warn_at_percent() -> u8 { 80 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppConfig {
@@ -21,6 +325,71 @@ pub struct AppConfig {
     pub notification_sound: bool,
     #[serde(default = "default_accent_color")]
     pub accent_color: String,
+    /// Tool policy applied to every session
+    #[serde(default)]
+    pub tool_policy: ToolPolicy,
+    /// Per-project policies keyed by project path, layered over `tool_policy`
+    #[serde(default)]
+    pub project_policies: HashMap<String, ToolPolicy>,
+    /// Spawn environment and extra arguments applied to every session
+    #[serde(default)]
+    pub spawn_profile: SpawnProfile,
+    /// Per-project spawn profiles keyed by project path, layered over `spawn_profile`
+    #[serde(default)]
+    pub project_profiles: HashMap<String, SpawnProfile>,
+    #[serde(default)]
+    pub shutdown: ShutdownTimeouts,
+    #[serde(default)]
+    pub restart: RestartPolicy,
+    #[serde(default)]
+    pub usage: UsageSettings,
+    /// Plugin registry index the marketplace lists: an `https://` URL or a local file
+    #[serde(default)]
+    pub plugin_registry: Option<String>,
+    /// Directory installed plugins are read from; `None` uses `~/.copilot/plugins`
+    #[serde(default)]
+    pub plugins_dir: Option<String>,
+}
+
+impl AppConfig {
+    /// Effective policy for a session in `working_dir`: the global policy plus the
+    /// override of the closest project directory containing it.
+    pub fn tool_policy_for(&self, working_dir: &str) -> ToolPolicy {
+        match closest_project(&self.project_policies, working_dir) {
+            Some(policy) => self.tool_policy.merged_with(policy),
+            None => self.tool_policy.clone(),
+        }
+    }
+
+    /// Effective spawn profile for a session in `working_dir`, resolved like `tool_policy_for`.
+    pub fn spawn_profile_for(&self, working_dir: &str) -> SpawnProfile {
+        match closest_project(&self.project_profiles, working_dir) {
+            Some(profile) => self.spawn_profile.merged_with(profile),
+            None => self.spawn_profile.clone(),
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        self.tool_policy.validate()?;
+        for policy in self.project_policies.values() {
+            policy.validate()?;
+        }
+        self.spawn_profile.validate()?;
+        for profile in self.project_profiles.values() {
+            profile.validate()?;
+        }
+        Ok(())
+    }
+}
+
+/// The entry of the deepest project directory containing `working_dir`
+fn closest_project<'a, T>(projects: &'a HashMap<String, T>, working_dir: &str) -> Option<&'a T> {
+    let dir = Path::new(working_dir);
+    projects
+        .iter()
+        .filter(|(path, _)| dir.starts_with(Path::new(path.as_str())))
+        .max_by_key(|(path, _)| Path::new(path.as_str()).components().count())
+        .map(|(_, value)| value)
 }
 
 fn default_font_size() -> u16 { 14 }
@@ -42,6 +411,15 @@ impl Default for AppConfig {
             send_on_enter: true,
             notification_sound: false,
             accent_color: "blue".to_string(),
+            tool_policy: ToolPolicy::default(),
+            project_policies: HashMap::new(),
+            spawn_profile: SpawnProfile::default(),
+            project_profiles: HashMap::new(),
+            shutdown: ShutdownTimeouts::default(),
+            restart: RestartPolicy::default(),
+            usage: UsageSettings::default(),
+            plugin_registry: None,
+            plugins_dir: None,
         }
     }
 }
@@ -100,6 +478,42 @@ impl ConfigManager {
         let _ = save_config(&config);
     }
 
+    /// Set or clear (`None`) the tool policy override for a project.
+    pub fn set_project_policy(
+        &self,
+        project_path: &str,
+        policy: Option<ToolPolicy>,
+    ) -> Result<(), std::io::Error> {
+        let mut config = self.config.lock().unwrap_or_else(|e| e.into_inner());
+        match policy {
+            Some(policy) => {
+                config.project_policies.insert(project_path.to_string(), policy);
+            }
+            None => {
+                config.project_policies.remove(project_path);
+            }
+        }
+        save_config(&config)
+    }
+
+    /// Set or clear (`None`) the spawn profile override for a project.
+    pub fn set_project_profile(
+        &self,
+        project_path: &str,
+        profile: Option<SpawnProfile>,
+    ) -> Result<(), std::io::Error> {
+        let mut config = self.config.lock().unwrap_or_else(|e| e.into_inner());
+        match profile {
+            Some(profile) => {
+                config.project_profiles.insert(project_path.to_string(), profile);
+            }
+            None => {
+                config.project_profiles.remove(project_path);
+            }
+        }
+        save_config(&config)
+    }
+
     pub fn set_theme(&self, theme: &str) {
         let mut config = self.config.lock().unwrap_or_else(|e| e.into_inner());
         config.theme = theme.to_string();
@@ -180,4 +594,154 @@ mod tests {
         *manager.config.lock().unwrap() = updated;
         assert_eq!(manager.get_config().theme, "light");
     }
+
+    #[test]
+    fn test_old_config_without_policies_loads() {
+        let json = r#"{"copilot_path":null,"default_model":null,"theme":"dark","recent_projects":[]}"#;
+        let config: AppConfig = serde_json::from_str(json).unwrap();
+        assert_eq!(config.tool_policy, ToolPolicy::default());
+        assert!(config.project_policies.is_empty());
+        assert_eq!(config.shutdown, ShutdownTimeouts::default());
+        assert_eq!(config.spawn_profile, SpawnProfile::default());
+    }
+
+    #[test]
+    fn test_extra_args_cannot_override_managed_flags() {
+        let mut profile = SpawnProfile {
+            extra_args: vec!["--no-color".into(), "--log-level".into(), "debug".into()],
+            ..SpawnProfile::default()
+        };
+        assert!(profile.validate().is_ok());
+        profile.extra_args.push("--model=gpt-5".into());
+        assert!(profile.validate().is_err());
+
+        profile.extra_args = vec!["--additional-mcp-config".into(), "@extra.json".into()];
+        assert!(profile.validate().is_err());
+    }
+
+    #[test]
+    fn test_project_profile_layers_over_global() {
+        let mut config = AppConfig::default();
+        config.spawn_profile.env.insert("HTTPS_PROXY".into(), "http://proxy:8080".into());
+        config.spawn_profile.env.insert("GH_HOST".into(), "github.com".into());
+        config.spawn_profile.add_dirs.push("/shared".into());
+        config.spawn_profile.path_prepend.push("/opt/tools/bin".into());
+        config.spawn_profile.extra_args = vec!["--log-level".into(), "info".into()];
+        config.project_profiles.insert(
+            "/work/ghes".into(),
+            SpawnProfile {
+                env: HashMap::from([("GH_HOST".into(), "github.example.com".into())]),
+                add_dirs: vec!["/work/libs".into()],
+                path_prepend: vec!["node_modules/.bin".into()],
+                extra_args: vec!["--log-level".into(), "debug".into()],
+                env_file: Some(".env.copilot".into()),
+            },
+        );
+
+        let profile = config.spawn_profile_for("/work/ghes/service");
+        assert_eq!(profile.env["GH_HOST"], "github.example.com");
+        assert_eq!(profile.env["HTTPS_PROXY"], "http://proxy:8080");
+        assert_eq!(profile.add_dirs, vec!["/shared", "/work/libs"]);
+        assert_eq!(profile.path_prepend, vec!["node_modules/.bin", "/opt/tools/bin"]);
+        assert_eq!(
+            profile.extra_args,
+            vec!["--log-level", "info", "--log-level", "debug"]
+        );
+        assert_eq!(profile.env_file.as_deref(), Some(".env.copilot"));
+        assert_eq!(config.spawn_profile_for("/work/other"), config.spawn_profile);
+    }
+
+    #[test]
+    fn test_resolve_env_reads_env_file_and_prepends_path() {
+        let dir = std::env::temp_dir().join(format!("copilot-env-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(".env"), "GITHUB_TOKEN=secret\nPATH=/usr/bin\n").unwrap();
+        let profile = SpawnProfile {
+            env: HashMap::from([("GH_HOST".into(), "github.example.com".into())]),
+            env_file: Some(".env".into()),
+            path_prepend: vec!["/opt/tools/bin".into()],
+            ..SpawnProfile::default()
+        };
+
+        let vars = profile.resolve_env(dir.to_str().unwrap()).unwrap();
+        let get = |key: &str| vars.iter().rev().find(|(k, _)| k == key).map(|(_, v)| v.as_str());
+        assert_eq!(get("GITHUB_TOKEN"), Some("secret"));
+        assert_eq!(get("GH_HOST"), Some("github.example.com"));
+        assert_eq!(get("PATH"), Some("/opt/tools/bin:/usr/bin"));
+
+        let missing = SpawnProfile {
+            env_file: Some("missing.env".into()),
+            ..SpawnProfile::default()
+        };
+        assert!(missing.resolve_env(dir.to_str().unwrap()).is_err());
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_partial_shutdown_timeouts_use_defaults() {
+        let timeouts: ShutdownTimeouts =
+            serde_json::from_str(r#"{"exit_timeout_ms":500}"#).unwrap();
+        assert_eq!(timeouts.exit_timeout(), Duration::from_millis(500));
+        assert_eq!(timeouts.term_timeout(), Duration::from_millis(2000));
+    }
+
+    #[test]
+    fn test_restart_backoff_doubles_up_to_cap() {
+        let policy = RestartPolicy {
+            enabled: true,
+            max_restarts: 10,
+            ..RestartPolicy::default()
+        };
+        let delays: Vec<_> = (0..7).map(|n| policy.backoff(n).unwrap().as_millis()).collect();
+        assert_eq!(delays, vec![1000, 2000, 4000, 8000, 16000, 30000, 30000]);
+        assert_eq!(policy.backoff(10), None);
+        assert_eq!(policy.backoff(u32::MAX), None);
+    }
+
+    #[test]
+    fn test_restart_disabled_by_default() {
+        assert_eq!(RestartPolicy::default().backoff(0), None);
+    }
+
+    #[test]
+    fn test_tool_rule_validation() {
+        let policy = ToolPolicy {
+            allow: vec!["shell(git status)".into(), "write(src/**)".into(), "github-mcp".into()],
+            deny: vec!["shell(rm *)".into()],
+            allow_all_paths: false,
+        };
+        assert!(policy.validate().is_ok());
+        for bad in ["shell(git", "write()", "", "bad name"] {
+            let policy = ToolPolicy { deny: vec![bad.into()], ..ToolPolicy::default() };
+            assert!(policy.validate().is_err(), "{bad} should be rejected");
+        }
+    }
+
+    #[test]
+    fn test_project_policy_layers_over_global() {
+        let mut config = AppConfig {
+            tool_policy: ToolPolicy {
+                deny: vec!["shell(rm *)".into()],
+                ..ToolPolicy::default()
+            },
+            ..AppConfig::default()
+        };
+        config.project_policies.insert(
+            "/work/app".into(),
+            ToolPolicy { allow: vec!["write(src/**)".into()], ..ToolPolicy::default() },
+        );
+        config.project_policies.insert(
+            "/work/app/sub".into(),
+            ToolPolicy { allow: vec!["shell(cargo test)".into()], ..ToolPolicy::default() },
+        );
+
+        let policy = config.tool_policy_for("/work/app/src");
+        assert_eq!(policy.allow, vec!["write(src/**)".to_string()]);
+        assert_eq!(policy.deny, vec!["shell(rm *)".to_string()]);
+
+        let nested = config.tool_policy_for("/work/app/sub");
+        assert_eq!(nested.allow, vec!["shell(cargo test)".to_string()]);
+
+        assert!(config.tool_policy_for("/work/other").allow.is_empty());
+    }
 }
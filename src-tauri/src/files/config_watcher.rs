@@ -0,0 +1,156 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use notify::{Config, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::auth;
+use crate::mcp::McpManager;
+use crate::models;
+use crate::types::{AppError, CopilotConfigChange};
+
+/// Editors and the CLI often write a file in several steps; changes closer together
+/// than this are reported once.
+const DEBOUNCE: Duration = Duration::from_millis(200);
+
+/// Files under `~/.copilot/` the app mirrors
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum WatchedFile {
+    Config,
+    McpConfig,
+}
+
+impl WatchedFile {
+    const ALL: [WatchedFile; 2] = [WatchedFile::Config, WatchedFile::McpConfig];
+
+    fn file_name(self) -> &'static str {
+        match self {
+            WatchedFile::Config => "config.json",
+            WatchedFile::McpConfig => "mcp-config.json",
+        }
+    }
+
+    fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?;
+        Self::ALL.into_iter().find(|f| f.file_name() == name)
+    }
+}
+
+/// Watches the Copilot CLI's config files and emits `auth-changed`,
+/// `copilot-config-changed` and `mcp-config-changed` when they change on disk.
+pub struct ConfigWatcher {
+    watcher: Mutex<Option<RecommendedWatcher>>,
+}
+
+impl ConfigWatcher {
+    pub fn new() -> Self {
+        Self {
+            watcher: Mutex::new(None),
+        }
+    }
+
+    pub fn start(&self, app_handle: AppHandle) -> Result<(), AppError> {
+        let home = dirs::home_dir().ok_or_else(|| AppError::Other("No home dir".into()))?;
+        let dir = home.join(".copilot");
+        // The CLI creates this on first run; watching needs it to exist already
+        std::fs::create_dir_all(&dir)?;
+
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = RecommendedWatcher::new(
+            move |res: Result<notify::Event, notify::Error>| {
+                let Ok(event) = res else {
+                    return;
+                };
+                if !matches!(
+                    event.kind,
+                    EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                ) {
+                    return;
+                }
+                for file in event.paths.iter().filter_map(|p| WatchedFile::from_path(p)) {
+                    let _ = tx.send(file);
+                }
+            },
+            Config::default(),
+        )
+        .map_err(|e| AppError::Other(format!("Watcher error: {}", e)))?;
+
+        // Watch the directory rather than the files, which are replaced on save
+        watcher
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| AppError::Other(format!("Watch error: {}", e)))?;
+
+        std::thread::spawn(move || dispatch_changes(rx, dir, app_handle));
+        *self.watcher.lock().unwrap_or_else(|e| e.into_inner()) = Some(watcher);
+        Ok(())
+    }
+}
+
+/// Turn bursts of file events into one app event per file whose contents changed.
+/// Ends when the watcher, and with it the sender, is dropped.
+fn dispatch_changes(rx: mpsc::Receiver<WatchedFile>, dir: PathBuf, app_handle: AppHandle) {
+    let read = |file: WatchedFile| std::fs::read_to_string(dir.join(file.file_name())).ok();
+    let mut contents: HashMap<WatchedFile, Option<String>> =
+        WatchedFile::ALL.into_iter().map(|f| (f, read(f))).collect();
+    let mut auth_status = auth::check_auth_status();
+
+    while let Ok(first) = rx.recv() {
+        let mut changed = HashSet::from([first]);
+        while let Ok(file) = rx.recv_timeout(DEBOUNCE) {
+            changed.insert(file);
+        }
+
+        for file in changed {
+            let current = read(file);
+            if contents.get(&file) == Some(&current) {
+                continue;
+            }
+            contents.insert(file, current);
+
+            match file {
+                WatchedFile::Config => {
+                    let change = CopilotConfigChange {
+                        model: models::cli_default_model(),
+                    };
+                    let _ = app_handle.emit("copilot-config-changed", change);
+
+                    let status = auth::check_auth_status();
+                    if status != auth_status {
+                        let _ = app_handle.emit("auth-changed", &status);
+                        auth_status = status;
+                    }
+                }
+                WatchedFile::McpConfig => {
+                    // A half-written or hand-edited file may not parse; the next save will.
+                    // Only the global file is watched, so only its servers are sent.
+                    match app_handle.state::<McpManager>().list_servers(None) {
+                        Ok(servers) => {
+                            let _ = app_handle.emit("mcp-config-changed", servers);
+                        }
+                        Err(e) => log::warn!("Ignoring MCP config change: {}", e),
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_watched_file_from_path() {
+        let dir = Path::new("/home/me/.copilot");
+        assert_eq!(WatchedFile::from_path(&dir.join("config.json")), Some(WatchedFile::Config));
+        assert_eq!(
+            WatchedFile::from_path(&dir.join("mcp-config.json")),
+            Some(WatchedFile::McpConfig)
+        );
+        assert_eq!(WatchedFile::from_path(&dir.join("config.json.tmp")), None);
+        assert_eq!(WatchedFile::from_path(&dir.join("session-state")), None);
+    }
+}
@@ -1,3 +1,5 @@
+mod config_watcher;
 mod watcher;
 
+pub use config_watcher::ConfigWatcher;
 pub use watcher::FileWatcher;
@@ -3,16 +3,21 @@ mod commands;
 mod config;
 mod files;
 mod mcp;
+mod models;
 mod plugins;
 mod pty;
 mod session;
 mod types;
+mod usage;
 
+use auth::LoginManager;
 use config::ConfigManager;
-use files::FileWatcher;
+use files::{ConfigWatcher, FileWatcher};
 use mcp::McpManager;
+use models::ModelCatalog;
 use pty::PtyManager;
-use session::SessionManager;
+use session::{EventStreams, SessionManager};
+use usage::UsageTracker;
 use tauri::{
     image::Image,
     menu::{MenuBuilder, MenuItemBuilder, PredefinedMenuItem},
@@ -77,28 +82,44 @@ fn setup_tray(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
+    let config = ConfigManager::new();
+    let copilot_path = config.get_config().copilot_path;
+
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_window_state::Builder::default().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
-        .manage(PtyManager::new())
+        .manage(PtyManager::new(copilot_path.as_deref()))
         .manage(SessionManager::new())
-        .manage(ConfigManager::new())
+        .manage(config)
         .manage(FileWatcher::new())
+        .manage(ConfigWatcher::new())
+        .manage(EventStreams::new())
         .manage(McpManager::new())
+        .manage(UsageTracker::new())
+        .manage(ModelCatalog::new())
+        .manage(LoginManager::new())
         .invoke_handler(tauri::generate_handler![
             commands::check_copilot_status,
+            commands::set_copilot_path,
             commands::create_session,
             commands::send_message,
+            commands::send_keys,
+            commands::interrupt_session,
+            commands::respond_to_permission,
             commands::resize_terminal,
+            commands::get_session_output,
+            commands::set_raw_output,
             commands::list_sessions,
             commands::close_session,
             commands::get_config,
             commands::set_theme,
             commands::select_directory,
             commands::check_auth,
+            commands::list_accounts,
+            commands::switch_account,
             commands::open_project,
             commands::get_recent_projects,
             commands::detect_copilot_binary,
@@ -110,9 +131,13 @@ pub fn run() {
             commands::update_mcp_server,
             commands::delete_mcp_server,
             commands::toggle_mcp_server,
+            commands::probe_mcp_server,
+            commands::preview_mcp_import,
+            commands::import_mcp_servers,
             commands::rename_session,
             commands::set_model,
             commands::set_mode,
+            commands::set_session_account,
             commands::list_available_models,
             commands::get_default_model,
             commands::send_slash_command,
@@ -123,13 +148,21 @@ pub fn run() {
             commands::uninstall_plugin,
             commands::update_plugin,
             commands::update_config,
+            commands::set_project_tool_policy,
+            commands::set_project_spawn_profile,
+            commands::preview_session_args,
             commands::get_usage_metrics,
+            commands::get_usage_history,
             commands::clear_session_history,
             commands::trigger_login,
+            commands::cancel_login,
             commands::list_copilot_sessions,
             commands::get_copilot_config,
             commands::resume_session,
             commands::get_session_events,
+            commands::get_session_timeline,
+            commands::list_restorable_sessions,
+            commands::restore_session,
         ])
         .setup(|app| {
             if cfg!(debug_assertions) {
@@ -150,6 +183,13 @@ pub fn run() {
 
             setup_tray(app)?;
 
+            // Resolved MCP secrets a crash left behind
+            mcp::clear_session_overrides();
+
+            if let Err(e) = app.state::<ConfigWatcher>().start(app.handle().clone()) {
+                log::warn!("Not watching Copilot config: {}", e);
+            }
+
             Ok(())
         })
         .on_window_event(|window, event| {
@@ -161,6 +201,12 @@ pub fn run() {
                 }
             }
         })
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|_app, event| {
+            if let tauri::RunEvent::Exit = event {
+                mcp::clear_session_overrides();
+                auth::restore_active_account();
+            }
+        });
 }
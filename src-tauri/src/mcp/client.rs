@@ -0,0 +1,227 @@
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Write};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use serde_json::{json, Value};
+
+use crate::types::AppError;
+
+/// Lines of stderr kept to explain a failure
+const STDERR_LINES: usize = 20;
+/// How long a killed server gets to close stderr before we stop waiting for its tail
+const STDERR_DRAIN: Duration = Duration::from_millis(500);
+/// Pipes close just before the process can be reaped; how long to wait for its status
+const EXIT_GRACE: Duration = Duration::from_millis(200);
+
+/// A JSON-RPC connection to an MCP server started over stdio, using the
+/// newline-delimited framing of the MCP stdio transport.
+pub struct StdioClient {
+    child: Child,
+    stdin: ChildStdin,
+    messages: mpsc::Receiver<Value>,
+    stderr: Arc<Mutex<VecDeque<String>>>,
+    stderr_closed: mpsc::Receiver<()>,
+    next_id: u64,
+}
+
+impl StdioClient {
+    pub fn spawn(
+        command: &str,
+        args: &[String],
+        env: Option<&HashMap<String, String>>,
+    ) -> Result<Self, AppError> {
+        // GUI apps often can't find `npx`/`uvx` by bare name the way a shell does
+        let program = which::which(command).unwrap_or_else(|_| command.into());
+        let mut cmd = Command::new(program);
+        cmd.args(args)
+            .envs(env.into_iter().flatten())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        // Own process group, so launchers like `npx` can be stopped with their children
+        #[cfg(unix)]
+        {
+            use std::os::unix::process::CommandExt;
+            cmd.process_group(0);
+        }
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| AppError::Other(format!("Failed to start `{command}`: {e}")))?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let stderr_pipe = child.stderr.take().expect("stderr is piped");
+
+        let (tx, messages) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                // Anything that isn't JSON-RPC is stray logging; skip it
+                if let Ok(message) = serde_json::from_str::<Value>(&line) {
+                    if tx.send(message).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let stderr = Arc::new(Mutex::new(VecDeque::new()));
+        let (closed_tx, stderr_closed) = mpsc::channel();
+        let tail = stderr.clone();
+        std::thread::spawn(move || {
+            for line in BufReader::new(stderr_pipe).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                let mut tail = tail.lock().unwrap_or_else(|e| e.into_inner());
+                if tail.len() == STDERR_LINES {
+                    tail.pop_front();
+                }
+                tail.push_back(line);
+            }
+            let _ = closed_tx.send(());
+        });
+
+        Ok(Self {
+            child,
+            stdin,
+            messages,
+            stderr,
+            stderr_closed,
+            next_id: 1,
+        })
+    }
+
+    /// Send a request and wait up to `timeout` for its response's `result`
+    pub fn request(
+        &mut self,
+        method: &str,
+        params: Value,
+        timeout: Duration,
+    ) -> Result<Value, AppError> {
+        let id = self.next_id;
+        self.next_id += 1;
+        let message = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params });
+        self.send(&message)
+            .map_err(|e| self.lost(method, format!("Failed to write to server: {e}")))?;
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let message = match self.messages.recv_timeout(remaining) {
+                Ok(message) => message,
+                Err(mpsc::RecvTimeoutError::Timeout) => {
+                    return Err(AppError::Other(format!(
+                        "No response to `{method}` within {}s",
+                        timeout.as_secs()
+                    )));
+                }
+                Err(mpsc::RecvTimeoutError::Disconnected) => {
+                    return Err(
+                        self.lost(method, format!("Server closed stdout during `{method}`"))
+                    );
+                }
+            };
+
+            if message.get("method").is_some() {
+                self.answer_server_request(&message)
+                    .map_err(|e| self.lost(method, format!("Failed to write to server: {e}")))?;
+                continue;
+            }
+            if message.get("id").and_then(|v| v.as_u64()) != Some(id) {
+                continue;
+            }
+            if let Some(error) = message.get("error") {
+                let text = error
+                    .get("message")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("unknown error");
+                return Err(AppError::Other(format!("`{method}` failed: {text}")));
+            }
+            return Ok(message.get("result").cloned().unwrap_or(Value::Null));
+        }
+    }
+
+    pub fn notify(&mut self, method: &str, params: Value) -> Result<(), AppError> {
+        self.send(&json!({ "jsonrpc": "2.0", "method": method, "params": params }))
+            .map_err(|e| self.lost(method, format!("Failed to write to server: {e}")))
+    }
+
+    /// The error for a connection that broke during `method`: the server's exit
+    /// status if it has exited, else `cause`
+    fn lost(&mut self, method: &str, cause: String) -> AppError {
+        AppError::Other(match self.exit_status() {
+            Some(status) => format!("Server exited ({status}) during `{method}`"),
+            None => cause,
+        })
+    }
+
+    fn exit_status(&mut self) -> Option<ExitStatus> {
+        let deadline = Instant::now() + EXIT_GRACE;
+        loop {
+            match self.child.try_wait() {
+                Ok(Some(status)) => return Some(status),
+                Ok(None) if Instant::now() < deadline => {
+                    std::thread::sleep(Duration::from_millis(10))
+                }
+                _ => return None,
+            }
+        }
+    }
+
+    /// Requests the server makes of us mid-handshake: answer pings, decline the rest.
+    /// Notifications need no answer.
+    fn answer_server_request(&mut self, message: &Value) -> std::io::Result<()> {
+        let Some(id) = message.get("id").cloned() else {
+            return Ok(());
+        };
+        let reply = if message.get("method").and_then(|v| v.as_str()) == Some("ping") {
+            json!({ "jsonrpc": "2.0", "id": id, "result": {} })
+        } else {
+            json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32601, "message": "Method not found" }
+            })
+        };
+        self.send(&reply)
+    }
+
+    fn send(&mut self, message: &Value) -> std::io::Result<()> {
+        let mut line = message.to_string();
+        line.push('\n');
+        self.stdin.write_all(line.as_bytes())?;
+        self.stdin.flush()
+    }
+
+    /// Stop the server and return the last lines it wrote to stderr
+    pub fn shutdown(mut self) -> Vec<String> {
+        self.kill();
+        let _ = self.stderr_closed.recv_timeout(STDERR_DRAIN);
+        let tail = self.stderr.lock().unwrap_or_else(|e| e.into_inner());
+        tail.iter().cloned().collect()
+    }
+
+    fn kill(&mut self) {
+        if let Ok(Some(_)) = self.child.try_wait() {
+            return;
+        }
+        #[cfg(unix)]
+        unsafe {
+            libc::killpg(self.child.id() as libc::pid_t, libc::SIGKILL);
+        }
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+impl Drop for StdioClient {
+    fn drop(&mut self) {
+        self.kill();
+    }
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{secrets, validate_server};
+use crate::types::{
+    AppError, McpImportCandidate, McpImportSource, McpScope, McpServerConfig, McpTransport,
+};
+
+/// A server entry as VS Code, Claude Desktop and Cursor write them. VS Code keys its
+/// servers by `servers`, the others by `mcpServers`; the entries share these fields.
+#[derive(Debug, Deserialize)]
+struct ForeignEntry {
+    #[serde(rename = "type", default)]
+    kind: Option<String>,
+    #[serde(default)]
+    command: Option<String>,
+    #[serde(default)]
+    args: Vec<String>,
+    #[serde(default)]
+    env: Option<HashMap<String, String>>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    headers: Option<HashMap<String, String>>,
+    #[serde(rename = "envFile", default)]
+    env_file: Option<String>,
+    #[serde(default)]
+    disabled: bool,
+}
+
+/// Config files other tools keep MCP servers in, whether or not they exist.
+/// `config_dir` is the platform's per-user config directory.
+pub fn source_paths(
+    home: &Path,
+    config_dir: Option<&Path>,
+    working_dir: Option<&str>,
+) -> Vec<(McpImportSource, PathBuf)> {
+    let mut paths = Vec::new();
+    if let Some(config_dir) = config_dir {
+        paths.push((
+            McpImportSource::VsCode,
+            config_dir.join("Code/User/mcp.json"),
+        ));
+        paths.push((
+            McpImportSource::ClaudeDesktop,
+            config_dir.join("Claude/claude_desktop_config.json"),
+        ));
+    }
+    paths.push((McpImportSource::Cursor, home.join(".cursor/mcp.json")));
+    if let Some(dir) = working_dir {
+        let dir = Path::new(dir);
+        paths.push((McpImportSource::VsCode, dir.join(".vscode/mcp.json")));
+        paths.push((McpImportSource::Cursor, dir.join(".cursor/mcp.json")));
+    }
+    paths
+}
+
+/// Servers in the config file `text` of `source`, each with anything that didn't carry
+/// over. `working_dir` replaces VS Code's `${workspaceFolder}`.
+pub fn parse_source(
+    source: McpImportSource,
+    text: &str,
+    working_dir: Option<&str>,
+) -> Result<Vec<(McpServerConfig, Vec<String>)>, AppError> {
+    let root: Value = serde_json::from_str(text)
+        .map_err(|e| AppError::Other(format!("Invalid {} config: {e}", source.label())))?;
+    let key = match source {
+        McpImportSource::VsCode => "servers",
+        McpImportSource::ClaudeDesktop | McpImportSource::Cursor => "mcpServers",
+    };
+    let Some(entries) = root.get(key).and_then(|v| v.as_object()) else {
+        return Ok(Vec::new());
+    };
+
+    let mut servers = Vec::new();
+    for (name, entry) in entries {
+        let entry: ForeignEntry = match serde_json::from_value(entry.clone()) {
+            Ok(entry) => entry,
+            Err(e) => {
+                log::warn!("Skipping {} MCP server '{name}': {e}", source.label());
+                continue;
+            }
+        };
+        match convert(name, entry, working_dir) {
+            Some(converted) => servers.push(converted),
+            None => log::warn!(
+                "Skipping {} MCP server '{name}': unknown type",
+                source.label()
+            ),
+        }
+    }
+    Ok(servers)
+}
+
+fn convert(
+    name: &str,
+    entry: ForeignEntry,
+    working_dir: Option<&str>,
+) -> Option<(McpServerConfig, Vec<String>)> {
+    let transport = match entry.kind.as_deref() {
+        Some("stdio") | Some("local") => McpTransport::Local,
+        Some("http") | Some("streamable-http") => McpTransport::Http,
+        Some("sse") => McpTransport::Sse,
+        Some(_) => return None,
+        None if entry.command.is_none() && entry.url.is_some() => McpTransport::Http,
+        None => McpTransport::Local,
+    };
+
+    let mut warnings = Vec::new();
+    let mut refs = |value: &str| {
+        let (value, warning) = convert_refs(value, working_dir);
+        warnings.extend(warning);
+        value
+    };
+    let base = McpServerConfig {
+        name: name.to_string(),
+        scope: McpScope::Global,
+        transport,
+        command: String::new(),
+        args: Vec::new(),
+        env: None,
+        url: None,
+        headers: None,
+        enabled: !entry.disabled,
+        status: None,
+        warnings: Vec::new(),
+    };
+    let server = match transport {
+        McpTransport::Local => McpServerConfig {
+            command: refs(entry.command.as_deref().unwrap_or_default()),
+            args: entry.args.iter().map(|arg| refs(arg)).collect(),
+            env: entry
+                .env
+                .map(|env| env.into_iter().map(|(k, v)| (k, refs(&v))).collect()),
+            ..base
+        },
+        McpTransport::Http | McpTransport::Sse => McpServerConfig {
+            url: entry.url.as_deref().map(&mut refs),
+            headers: entry.headers.map(|headers| {
+                headers
+                    .into_iter()
+                    .map(|(k, v)| (k, refs(&v)))
+                    .collect()
+            }),
+            ..base
+        },
+    };
+    if let Some(env_file) = entry.env_file {
+        warnings.push(format!(
+            "Variables from envFile '{env_file}' were not imported"
+        ));
+    }
+    if let Err(e) = validate_server(&server) {
+        warnings.push(e.to_string());
+    }
+    warnings.extend(secrets::credential_warnings(&server));
+    warnings.dedup();
+    Some((server, warnings))
+}
+
+/// Rewrite VS Code-style references: `${workspaceFolder}` becomes the working
+/// directory, and `${env:NAME}` is kept as is. Returns a warning for references that
+/// can't be carried over, like `${input:id}` prompts.
+fn convert_refs(value: &str, working_dir: Option<&str>) -> (String, Option<String>) {
+    let mut value = value.to_string();
+    if let Some(dir) = working_dir {
+        value = value.replace("${workspaceFolder}", dir);
+    }
+    let warning = value
+        .match_indices("${")
+        .map(|(start, _)| {
+            let reference = &value[start..];
+            let end = reference.find('}').map_or(reference.len(), |i| i + 1);
+            &reference[..end]
+        })
+        .find(|reference| {
+            let supported = reference.starts_with("${env:") || reference.starts_with("${file:");
+            (reference.contains(':') && !supported) || *reference == "${workspaceFolder}"
+        })
+        .map(|reference| format!("{reference} has no equivalent; set it after importing"));
+    (value, warning)
+}
+
+/// Every server found in other tools' configs, flagged where its name is already
+/// taken by one of `existing`
+pub fn preview(existing: &[McpServerConfig], working_dir: Option<&str>) -> Vec<McpImportCandidate> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    let config_dir = dirs::config_dir();
+    let mut candidates = Vec::new();
+    for (source, path) in source_paths(&home, config_dir.as_deref(), working_dir) {
+        let Ok(text) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        let servers = match parse_source(source, &text, working_dir) {
+            Ok(servers) => servers,
+            Err(e) => {
+                log::warn!("{}: {e}", path.display());
+                continue;
+            }
+        };
+        for (server, warnings) in servers {
+            let conflict = existing
+                .iter()
+                .find(|s| s.name == server.name)
+                .map(|s| s.scope);
+            candidates.push(McpImportCandidate {
+                source,
+                path: path.to_string_lossy().into_owned(),
+                server,
+                conflict,
+                warnings,
+            });
+        }
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vscode_config() {
+        let text = r#"{
+            "inputs": [{ "id": "api-key", "type": "promptString" }],
+            "servers": {
+                "github": {
+                    "type": "http",
+                    "url": "https://api.githubcopilot.com/mcp/",
+                    "headers": { "Authorization": "Bearer ${env:GITHUB_TOKEN}" }
+                },
+                "files": {
+                    "type": "stdio",
+                    "command": "npx",
+                    "args": ["-y", "server-fs", "${workspaceFolder}"],
+                    "env": { "API_KEY": "${input:api-key}", "TOKEN": "${env:GH_TOKEN}" }
+                },
+                "odd": { "type": "websocket", "url": "wss://example.com" }
+            }
+        }"#;
+        let servers = parse_source(McpImportSource::VsCode, text, Some("/work/app")).unwrap();
+        assert_eq!(servers.len(), 2);
+
+        let (github, warnings) = &servers[0];
+        assert_eq!(github.transport, McpTransport::Http);
+        assert_eq!(
+            github.headers.as_ref().unwrap()["Authorization"],
+            "Bearer ${env:GITHUB_TOKEN}"
+        );
+        assert!(warnings.is_empty());
+
+        let (files, warnings) = &servers[1];
+        assert_eq!(files.args, ["-y", "server-fs", "/work/app"]);
+        assert_eq!(files.env.as_ref().unwrap()["TOKEN"], "${env:GH_TOKEN}");
+        assert_eq!(
+            warnings[0],
+            "${input:api-key} has no equivalent; set it after importing"
+        );
+        assert!(warnings[1].contains("invalid variable name 'input:api-key'"));
+    }
+
+    #[test]
+    fn test_parse_claude_and_cursor_configs() {
+        let claude = r#"{ "mcpServers": { "fs": { "command": "npx", "args": ["server-fs"] } } }"#;
+        let servers = parse_source(McpImportSource::ClaudeDesktop, claude, None).unwrap();
+        assert_eq!(servers[0].0.transport, McpTransport::Local);
+        assert!(servers[0].1.is_empty());
+
+        let cursor = r#"{ "mcpServers": { "remote": { "url": "https://example.com/mcp" } } }"#;
+        let servers = parse_source(McpImportSource::Cursor, cursor, None).unwrap();
+        assert_eq!(servers[0].0.transport, McpTransport::Http);
+        assert!(parse_source(McpImportSource::Cursor, "{", None).is_err());
+    }
+}
@@ -0,0 +1,202 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+use super::client::StdioClient;
+use crate::types::{AppError, McpHealth, McpProbeResult, McpServerConfig, McpTransport};
+
+const PROTOCOL_VERSION: &str = "2025-06-18";
+/// Launchers like `npx -y` may download the server first
+const INITIALIZE_TIMEOUT: Duration = Duration::from_secs(30);
+const LIST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Stop following `nextCursor` after this many pages
+const MAX_PAGES: usize = 20;
+
+/// Start `server`, run the `initialize` handshake and list what it offers.
+/// A server that fails to start or answer is reported as failing, not as an error.
+pub fn probe(server: &McpServerConfig) -> Result<McpProbeResult, AppError> {
+    if server.transport != McpTransport::Local {
+        return Err(AppError::Other(format!(
+            "MCP server '{}' is remote; only local servers can be probed",
+            server.name
+        )));
+    }
+
+    let mut result = McpProbeResult {
+        name: server.name.clone(),
+        health: McpHealth::Failing,
+        server_info: None,
+        protocol_version: None,
+        startup_ms: None,
+        tools: Vec::new(),
+        resources: Vec::new(),
+        prompts: Vec::new(),
+        error: None,
+        stderr_tail: Vec::new(),
+        checked_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+    };
+
+    let started = Instant::now();
+    let mut client = match StdioClient::spawn(&server.command, &server.args, server.env.as_ref()) {
+        Ok(client) => client,
+        Err(e) => {
+            result.error = Some(e.to_string());
+            return Ok(result);
+        }
+    };
+    match introspect(&mut client, &mut result, started) {
+        Ok(()) => result.health = McpHealth::Healthy,
+        Err(e) => result.error = Some(e.to_string()),
+    }
+    result.stderr_tail = client.shutdown();
+    Ok(result)
+}
+
+fn introspect(
+    client: &mut StdioClient,
+    result: &mut McpProbeResult,
+    started: Instant,
+) -> Result<(), AppError> {
+    let init = client.request(
+        "initialize",
+        json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "copilot-desktop", "version": env!("CARGO_PKG_VERSION") },
+        }),
+        INITIALIZE_TIMEOUT,
+    )?;
+    result.startup_ms = Some(started.elapsed().as_millis() as u64);
+    result.protocol_version = init
+        .get("protocolVersion")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+    result.server_info = init.get("serverInfo").and_then(|info| {
+        let name = info.get("name").and_then(|v| v.as_str())?;
+        Some(match info.get("version").and_then(|v| v.as_str()) {
+            Some(version) => format!("{name} {version}"),
+            None => name.to_string(),
+        })
+    });
+    client.notify("notifications/initialized", json!({}))?;
+
+    // Only ask for what the server says it has; the others would be errors
+    let capabilities = init.get("capabilities").cloned().unwrap_or(Value::Null);
+    if capabilities.get("tools").is_some() {
+        result.tools = list_all(client, "tools/list", "tools")?;
+    }
+    if capabilities.get("resources").is_some() {
+        result.resources = list_all(client, "resources/list", "resources")?;
+    }
+    if capabilities.get("prompts").is_some() {
+        result.prompts = list_all(client, "prompts/list", "prompts")?;
+    }
+    Ok(())
+}
+
+/// Every item of a paginated `*/list` method
+fn list_all<T: DeserializeOwned>(
+    client: &mut StdioClient,
+    method: &str,
+    key: &str,
+) -> Result<Vec<T>, AppError> {
+    let mut items = Vec::new();
+    let mut cursor: Option<String> = None;
+    for _ in 0..MAX_PAGES {
+        let params = match &cursor {
+            Some(cursor) => json!({ "cursor": cursor }),
+            None => json!({}),
+        };
+        let page = client.request(method, params, LIST_TIMEOUT)?;
+        let page_items = page.get(key).cloned().unwrap_or(Value::Array(Vec::new()));
+        let page_items: Vec<T> = serde_json::from_value(page_items)
+            .map_err(|e| AppError::Other(format!("Invalid `{method}` response: {e}")))?;
+        items.extend(page_items);
+
+        cursor = page
+            .get("nextCursor")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string());
+        if cursor.is_none() {
+            break;
+        }
+    }
+    Ok(items)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use crate::types::{McpScope, McpTool};
+
+    /// A stand-in server: `sh` answering the handshake with canned responses
+    fn stand_in(script: &str) -> McpServerConfig {
+        McpServerConfig {
+            name: "stand-in".to_string(),
+            scope: McpScope::Global,
+            transport: McpTransport::Local,
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            env: None,
+            url: None,
+            headers: None,
+            enabled: true,
+            status: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_probe_healthy_server() {
+        let script = r#"
+            read -r init
+            echo 'starting up' >&2
+            echo '{"jsonrpc":"2.0","id":1,"result":{"protocolVersion":"2025-06-18","capabilities":{"tools":{}},"serverInfo":{"name":"stand-in","version":"1.0.0"}}}'
+            read -r initialized
+            read -r list
+            echo '{"jsonrpc":"2.0","method":"notifications/message","params":{}}'
+            echo '{"jsonrpc":"2.0","id":2,"result":{"tools":[{"name":"echo","description":"Echo input","inputSchema":{}}],"nextCursor":"p2"}}'
+            read -r list
+            echo '{"jsonrpc":"2.0","id":3,"result":{"tools":[{"name":"sum"}]}}'
+            read -r done
+        "#;
+        let result = probe(&stand_in(script)).unwrap();
+        assert_eq!(result.health, McpHealth::Healthy, "{:?}", result.error);
+        assert_eq!(result.server_info.as_deref(), Some("stand-in 1.0.0"));
+        assert!(result.startup_ms.is_some());
+        assert_eq!(
+            result.tools,
+            [
+                McpTool {
+                    name: "echo".to_string(),
+                    description: Some("Echo input".to_string()),
+                },
+                McpTool {
+                    name: "sum".to_string(),
+                    description: None,
+                },
+            ]
+        );
+        assert!(result.resources.is_empty());
+        assert_eq!(result.stderr_tail, ["starting up"]);
+    }
+
+    #[test]
+    fn test_probe_failing_server() {
+        let result = probe(&stand_in("echo 'missing API_KEY' >&2; exit 3")).unwrap();
+        assert_eq!(result.health, McpHealth::Failing);
+        assert!(result.error.unwrap().contains("exited"));
+        assert_eq!(result.stderr_tail, ["missing API_KEY"]);
+
+        let missing = McpServerConfig {
+            command: "definitely-not-an-mcp-server".to_string(),
+            args: Vec::new(),
+            ..stand_in("")
+        };
+        assert_eq!(probe(&missing).unwrap().health, McpHealth::Failing);
+    }
+}
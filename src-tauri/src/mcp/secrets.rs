@@ -0,0 +1,265 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::types::McpServerConfig;
+
+/// Shown by `list_servers` in place of a literal env or header value that looks like
+/// a credential. Sending it back in an update keeps the stored value.
+pub const REDACTED: &str = "********";
+
+/// Classic, OAuth, user-to-server, server-to-server and refresh tokens, and
+/// fine-grained personal access tokens
+const GITHUB_TOKEN_PREFIXES: &[&str] = &["ghp_", "gho_", "ghu_", "ghs_", "ghr_", "github_pat_"];
+/// Shorter values are not flagged by entropy alone
+const MIN_SECRET_LEN: usize = 20;
+/// Bits per character above which a long value reads as random
+const MIN_SECRET_ENTROPY: f64 = 3.5;
+
+/// Where a `${env:NAME}` or `${file:path}` reference reads its value from
+enum SecretSource<'a> {
+    Env(&'a str),
+    File(&'a str),
+}
+
+/// Whether `value` has `${env:NAME}` or `${file:path}` references. A `$` not followed
+/// by `{` is literal.
+pub fn has_refs(value: &str) -> bool {
+    value.contains("${")
+}
+
+/// `value` with every reference replaced by what `lookup` returns for it
+fn substitute<F>(value: &str, mut lookup: F) -> Result<String, String>
+where
+    F: FnMut(SecretSource) -> Result<String, String>,
+{
+    let mut resolved = String::new();
+    let mut rest = value;
+    while let Some(start) = rest.find("${") {
+        resolved.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after
+            .find('}')
+            .ok_or_else(|| format!("unclosed '${{' in '{value}'"))?;
+        let reference = &after[..end];
+        // A bare `${NAME}` is kept as shorthand for `${env:NAME}`
+        let source = match reference.strip_prefix("file:") {
+            Some(path) => SecretSource::File(path),
+            None => SecretSource::Env(reference.strip_prefix("env:").unwrap_or(reference)),
+        };
+        resolved.push_str(&lookup(source)?);
+        rest = &after[end + 1..];
+    }
+    resolved.push_str(rest);
+    Ok(resolved)
+}
+
+/// Check the references in `value` without resolving them
+pub fn check_refs(value: &str) -> Result<(), String> {
+    substitute(value, |source| {
+        match source {
+            SecretSource::Env(name) => {
+                let valid = name
+                    .chars()
+                    .next()
+                    .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+                    && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+                if !valid {
+                    return Err(format!("invalid variable name '{name}'"));
+                }
+            }
+            SecretSource::File(path) if path.trim().is_empty() => {
+                return Err("`${file:}` needs a path".to_string());
+            }
+            SecretSource::File(_) => {}
+        }
+        Ok(String::new())
+    })
+    .map(|_| ())
+}
+
+/// Replace the references in `value`: `${env:NAME}` with `env`'s value for `NAME`,
+/// `${file:path}` with the file's contents minus a trailing newline. Relative paths
+/// are taken from `base_dir`.
+pub fn resolve(
+    value: &str,
+    env: &dyn Fn(&str) -> Option<String>,
+    base_dir: &Path,
+) -> Result<String, String> {
+    substitute(value, |source| match source {
+        SecretSource::Env(name) => {
+            env(name).ok_or_else(|| format!("environment variable {name} is not set"))
+        }
+        SecretSource::File(path) => {
+            let path = secret_path(path, base_dir);
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("failed to read {}: {e}", path.display()))?;
+            Ok(content.trim_end_matches(['\r', '\n']).to_string())
+        }
+    })
+}
+
+fn secret_path(path: &str, base_dir: &Path) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => base_dir.join(path),
+    }
+}
+
+/// Resolve every value of an env or header map, naming the key in the error
+pub fn resolve_all(
+    values: &HashMap<String, String>,
+    env: &dyn Fn(&str) -> Option<String>,
+    base_dir: &Path,
+) -> Result<HashMap<String, String>, String> {
+    values
+        .iter()
+        .map(|(key, value)| {
+            resolve(value, env, base_dir)
+                .map(|value| (key.clone(), value))
+                .map_err(|e| format!("{key}: {e}"))
+        })
+        .collect()
+}
+
+/// Whether a literal value is probably a token: a GitHub token prefix, or a long
+/// random-looking string that isn't a URL or path
+pub fn looks_like_credential(value: &str) -> bool {
+    if GITHUB_TOKEN_PREFIXES.iter().any(|p| value.starts_with(p)) {
+        return true;
+    }
+    if value.len() < MIN_SECRET_LEN
+        || value.contains(char::is_whitespace)
+        || value.contains("://")
+        || value.starts_with(['/', '~', '.'])
+    {
+        return false;
+    }
+    let mixed = value.chars().any(|c| c.is_ascii_lowercase())
+        && value.chars().any(|c| c.is_ascii_uppercase())
+        && value.chars().any(|c| c.is_ascii_digit());
+    let hex = value.len() >= 32 && value.chars().all(|c| c.is_ascii_hexdigit());
+    (mixed || hex) && entropy(value) >= MIN_SECRET_ENTROPY
+}
+
+/// Shannon entropy in bits per character
+fn entropy(value: &str) -> f64 {
+    let mut counts = HashMap::new();
+    for c in value.chars() {
+        *counts.entry(c).or_insert(0usize) += 1;
+    }
+    let len = value.chars().count() as f64;
+    counts
+        .values()
+        .map(|&n| {
+            let p = n as f64 / len;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+/// Whether a value without references holds a credential, on its own or after an
+/// auth scheme like `Bearer`
+fn is_plain_secret(value: &str) -> bool {
+    !has_refs(value) && value.split_whitespace().last().is_some_and(looks_like_credential)
+}
+
+/// A warning for each literal env or header value of `server` that looks like a
+/// credential
+pub fn credential_warnings(server: &McpServerConfig) -> Vec<String> {
+    let mut warnings = plain_secrets(server.env.as_ref(), "");
+    warnings.extend(plain_secrets(server.headers.as_ref(), "header "));
+    warnings
+}
+
+fn plain_secrets(values: Option<&HashMap<String, String>>, label: &str) -> Vec<String> {
+    let mut keys: Vec<&String> = values
+        .into_iter()
+        .flatten()
+        .filter(|(_, value)| is_plain_secret(value))
+        .map(|(key, _)| key)
+        .collect();
+    keys.sort();
+    keys.into_iter()
+        .map(|key| {
+            format!(
+                "{label}{key} looks like a credential stored in plain text; \
+                 use ${{env:NAME}} or ${{file:path}} instead"
+            )
+        })
+        .collect()
+}
+
+/// `server` with credential-like literal env and header values replaced by `REDACTED`
+pub fn redact(server: &mut McpServerConfig) {
+    for value in server
+        .env
+        .iter_mut()
+        .chain(server.headers.iter_mut())
+        .flat_map(|values| values.values_mut())
+    {
+        if is_plain_secret(value) {
+            *value = REDACTED.to_string();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_refs() {
+        let dir = std::env::temp_dir().join(format!("copilot-secrets-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("token"), "s3cret\n").unwrap();
+        let env = |name: &str| (name == "USER_NAME").then(|| "octocat".to_string());
+
+        let value = "${env:USER_NAME}:${file:token} costs $5";
+        assert_eq!(
+            resolve(value, &env, &dir).unwrap(),
+            "octocat:s3cret costs $5"
+        );
+        assert_eq!(resolve("${USER_NAME}", &env, &dir).unwrap(), "octocat");
+        assert!(resolve("${MISSING}", &env, &dir)
+            .unwrap_err()
+            .contains("MISSING"));
+        assert!(resolve("${file:absent}", &env, &dir).is_err());
+
+        assert!(check_refs("Bearer ${env:TOKEN} ${_B2} ${file:~/.token}").is_ok());
+        assert!(check_refs("${1BAD}").is_err());
+        assert!(check_refs("${env:}").is_err());
+        assert!(check_refs("${env:MY-TOKEN}").is_err());
+        assert!(check_refs("${file:}").is_err());
+        assert!(check_refs("Bearer ${TOKEN").is_err());
+    }
+
+    #[test]
+    fn test_looks_like_credential() {
+        assert!(looks_like_credential("ghp_abc123"));
+        assert!(looks_like_credential("github_pat_11ABCDEFG"));
+        assert!(looks_like_credential("sk-Pq7xT2mZ9vL4nR8wK3yB6cH1"));
+        assert!(looks_like_credential("3f9a1c7e2b8d4f60a5e19c3b7d2f8a4e"));
+
+        assert!(!looks_like_credential("1"));
+        assert!(!looks_like_credential("my-app-staging-environment-2024"));
+        assert!(!looks_like_credential("https://api.example.com/v1/Token9"));
+        assert!(!looks_like_credential("/Users/octocat/Projects/App2024"));
+
+        let env = HashMap::from([
+            ("TOKEN".to_string(), "ghp_abc123".to_string()),
+            ("REF".to_string(), "${env:GITHUB_TOKEN}".to_string()),
+            ("DEBUG".to_string(), "1".to_string()),
+        ]);
+        let warnings = plain_secrets(Some(&env), "");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("TOKEN "));
+
+        let headers = HashMap::from([
+            ("Authorization".to_string(), "Bearer ghp_abc123".to_string()),
+            ("X-Api-Key".to_string(), "Bearer ${env:API_KEY}".to_string()),
+        ]);
+        let warnings = plain_secrets(Some(&headers), "header ");
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("header Authorization "));
+    }
+}
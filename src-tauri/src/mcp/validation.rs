@@ -0,0 +1,119 @@
+use super::secrets;
+use crate::types::{AppError, McpServerConfig, McpTransport};
+
+/// Check that `server` has what its transport needs before it is written to the config
+pub fn validate_server(server: &McpServerConfig) -> Result<(), AppError> {
+    let invalid =
+        |reason: String| AppError::Other(format!("MCP server '{}': {}", server.name, reason));
+
+    if server.name.trim().is_empty() {
+        return Err(AppError::Other("MCP server name is required".to_string()));
+    }
+    match server.transport {
+        McpTransport::Local => {
+            if server.command.trim().is_empty() {
+                return Err(invalid("a command is required".to_string()));
+            }
+            if server.url.is_some() || server.headers.is_some() {
+                return Err(invalid(
+                    "url and headers only apply to http and sse servers".to_string(),
+                ));
+            }
+            for (name, value) in server.env.iter().flatten() {
+                secrets::check_refs(value).map_err(|e| invalid(format!("env {name}: {e}")))?;
+            }
+        }
+        McpTransport::Http | McpTransport::Sse => {
+            let url = server.url.as_deref().unwrap_or_default().trim();
+            if url.is_empty() {
+                return Err(invalid("a URL is required".to_string()));
+            }
+            validate_url(url).map_err(invalid)?;
+            if !server.command.is_empty() || !server.args.is_empty() {
+                return Err(invalid(
+                    "command and args only apply to local servers".to_string(),
+                ));
+            }
+            for (name, value) in server.headers.iter().flatten() {
+                validate_header(name, value).map_err(invalid)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn validate_url(url: &str) -> Result<(), String> {
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))
+        .ok_or_else(|| format!("URL must start with http:// or https://: {url}"))?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or_default();
+    if host.is_empty() || url.chars().any(char::is_whitespace) {
+        return Err(format!("not a valid URL: {url}"));
+    }
+    Ok(())
+}
+
+fn validate_header(name: &str, value: &str) -> Result<(), String> {
+    // RFC 9110 token characters
+    let is_token = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || "!#$%&'*+-.^_`|~".contains(c));
+    if !is_token {
+        return Err(format!("invalid header name '{name}'"));
+    }
+    if value.contains(['\r', '\n']) {
+        return Err(format!("header '{name}' must be a single line"));
+    }
+    secrets::check_refs(value).map_err(|e| format!("header '{name}': {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::McpScope;
+
+    fn remote(url: &str, headers: &[(&str, &str)]) -> McpServerConfig {
+        McpServerConfig {
+            name: "remote".to_string(),
+            scope: McpScope::Global,
+            transport: McpTransport::Http,
+            command: String::new(),
+            args: Vec::new(),
+            env: None,
+            url: Some(url.to_string()),
+            headers: Some(
+                headers
+                    .iter()
+                    .map(|(k, v)| (k.to_string(), v.to_string()))
+                    .collect(),
+            ),
+            enabled: true,
+            status: None,
+            warnings: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_validate_remote_server() {
+        let auth = [("Authorization", "Bearer ${env:GITHUB_TOKEN}")];
+        assert!(validate_server(&remote("https://api.example.com/mcp", &auth)).is_ok());
+        assert!(validate_server(&remote("ftp://example.com", &auth)).is_err());
+        assert!(validate_server(&remote("https:///mcp", &auth)).is_err());
+        assert!(validate_server(&remote("https://example.com", &[("Bad Name", "x")])).is_err());
+        assert!(validate_server(&remote("https://example.com", &[("X-Key", "${1KEY}")])).is_err());
+        let file = [("X-Key", "${file:~/.config/key}")];
+        assert!(validate_server(&remote("https://example.com", &file)).is_ok());
+
+        let mut local = remote("https://example.com", &[]);
+        local.transport = McpTransport::Local;
+        assert!(validate_server(&local).is_err());
+        local.url = None;
+        local.headers = None;
+        local.command = "npx".to_string();
+        assert!(validate_server(&local).is_ok());
+        local.env = Some([("TOKEN".to_string(), "${file:}".to_string())].into());
+        assert!(validate_server(&local).is_err());
+    }
+}
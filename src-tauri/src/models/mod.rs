@@ -0,0 +1,419 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::pty::cli::{self, CopilotCli};
+use crate::session::events::CopilotEvent;
+use crate::types::{Account, ModelInfo};
+
+/// How long a model list read from the CLI is trusted before probing again
+const CACHE_TTL_SECS: u64 = 24 * 60 * 60;
+
+/// Models this app knows how to describe: id, name, provider, description, and the
+/// premium request multiplier. Also the model list when the CLI can't be probed.
+#[rustfmt::skip]
+const KNOWN_MODELS: &[(&str, &str, &str, &str, Option<f32>)] = &[
+    ("claude-sonnet-4.5", "Claude Sonnet 4.5", "Anthropic", "Best balance of speed and intelligence", Some(1.0)),
+    ("claude-haiku-4.5", "Claude Haiku 4.5", "Anthropic", "Fast and lightweight", Some(0.33)),
+    ("claude-opus-4.6", "Claude Opus 4.6", "Anthropic", "Most capable Anthropic model", Some(3.0)),
+    ("claude-opus-4.6-fast", "Claude Opus 4.6 Fast", "Anthropic", "Opus 4.6 with faster responses", None),
+    ("claude-opus-4.5", "Claude Opus 4.5", "Anthropic", "Previous generation flagship", Some(3.0)),
+    ("claude-sonnet-4", "Claude Sonnet 4", "Anthropic", "Fast and capable", Some(1.0)),
+    ("gemini-3-pro-preview", "Gemini 3 Pro", "Google", "Preview of Google's latest", Some(1.0)),
+    ("gpt-5.2-codex", "GPT-5.2 Codex", "OpenAI", "Latest codex model", Some(1.0)),
+    ("gpt-5.2", "GPT-5.2", "OpenAI", "Latest GPT model", Some(1.0)),
+    ("gpt-5.1-codex-max", "GPT-5.1 Codex Max", "OpenAI", "Maximum capability codex", Some(1.0)),
+    ("gpt-5.1-codex", "GPT-5.1 Codex", "OpenAI", "Codex optimized", Some(1.0)),
+    ("gpt-5.1", "GPT-5.1", "OpenAI", "GPT-5.1 generation", Some(1.0)),
+    ("gpt-5", "GPT-5", "OpenAI", "OpenAI flagship", Some(1.0)),
+    ("gpt-5.1-codex-mini", "GPT-5.1 Codex Mini", "OpenAI", "Small and fast codex", Some(0.33)),
+    ("gpt-5-mini", "GPT-5 Mini", "OpenAI", "Small and efficient", Some(0.0)),
+    ("gpt-4.1", "GPT-4.1", "OpenAI", "Previous generation", Some(0.0)),
+];
+
+/// Model ids read from one CLI version's `--help`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ModelProbe {
+    pub cli_version: String,
+    /// Unix seconds
+    pub fetched_at: u64,
+    pub models: Vec<String>,
+}
+
+impl ModelProbe {
+    fn is_fresh(&self, cli_version: &str, now: u64) -> bool {
+        self.cli_version == cli_version && now.saturating_sub(self.fetched_at) < CACHE_TTL_SECS
+    }
+}
+
+/// On-disk format of `~/.copilot-desktop/models.json`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ModelCache {
+    #[serde(default)]
+    pub probe: Option<ModelProbe>,
+    /// Per account (`availability_key`), models seen working (`true`) or rejected (`false`)
+    #[serde(default)]
+    pub availability: HashMap<String, HashMap<String, bool>>,
+}
+
+/// Models offered by the installed Copilot CLI, cached per CLI version.
+pub struct ModelCatalog {
+    cache: Mutex<ModelCache>,
+    cache_path: Option<PathBuf>,
+}
+
+impl ModelCatalog {
+    pub fn new() -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        Self::with_cache(Some(home.join(".copilot-desktop").join("models.json")))
+    }
+
+    /// A catalog persisting to `cache_path`; `None` keeps the cache in memory only.
+    pub fn with_cache(cache_path: Option<PathBuf>) -> Self {
+        let cache = cache_path.as_deref().map(load_cache).unwrap_or_default();
+        Self {
+            cache: Mutex::new(cache),
+            cache_path,
+        }
+    }
+
+    /// Models offered by `cli`. The CLI is only probed when the cache is
+    /// older than a day, was filled by another CLI version, or `refresh` is set.
+    pub fn list(
+        &self,
+        cli: Option<&CopilotCli>,
+        account: Option<&Account>,
+        refresh: bool,
+    ) -> Vec<ModelInfo> {
+        let ids = match cli {
+            Some(cli) => self.model_ids(cli, refresh),
+            None => self.cached_ids(),
+        };
+        let ids = ids.unwrap_or_else(|| KNOWN_MODELS.iter().map(|m| m.0.to_string()).collect());
+
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let availability = account.and_then(|a| cache.availability.get(&availability_key(a)));
+        ids.iter()
+            .map(|id| ModelInfo {
+                available: availability.and_then(|a| a.get(id).copied()),
+                ..describe(id)
+            })
+            .collect()
+    }
+
+    fn cached_ids(&self) -> Option<Vec<String>> {
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache.probe.as_ref().map(|p| p.models.clone())
+    }
+
+    fn model_ids(&self, cli: &CopilotCli, refresh: bool) -> Option<Vec<String>> {
+        let version = cli.version.map(|v| v.to_string()).unwrap_or_default();
+        let now = now_secs();
+        {
+            let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(probe) = cache
+                .probe
+                .as_ref()
+                .filter(|p| !refresh && p.is_fresh(&version, now))
+            {
+                return Some(probe.models.clone());
+            }
+        }
+
+        let models = cli::run(&cli.path, &["--help"])
+            .map(|help| parse_model_choices(&help))
+            .unwrap_or_default();
+        if models.is_empty() {
+            // Keep serving the last good list rather than dropping to the built-in one
+            return self.cached_ids();
+        }
+
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        cache.probe = Some(ModelProbe {
+            cli_version: version,
+            fetched_at: now,
+            models: models.clone(),
+        });
+        self.save(&cache);
+        Some(models)
+    }
+
+    /// Remember whether `account` could use `model`. Returns true when this changed
+    /// what was known.
+    pub fn set_available(&self, account: &Account, model: &str, available: bool) -> bool {
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+        let previous = cache
+            .availability
+            .entry(availability_key(account))
+            .or_default()
+            .insert(model.to_string(), available);
+        if previous == Some(available) {
+            return false;
+        }
+        self.save(&cache);
+        true
+    }
+
+    fn save(&self, cache: &ModelCache) {
+        if let Some(path) = &self.cache_path {
+            let _ = save_cache(path, cache);
+        }
+    }
+}
+
+/// Availability is kept per server and login, since the same login on github.com and
+/// on a GHES host are different accounts with different plans
+fn availability_key(account: &Account) -> String {
+    format!("{}/{}", account.host.trim_end_matches('/'), account.login)
+}
+
+/// Model availability an event proves: a usage report means the model answered,
+/// an error naming a model as unavailable means the account can't use it.
+pub fn availability_from_event(
+    event: &CopilotEvent,
+    known: &[ModelInfo],
+) -> Option<(String, bool)> {
+    match event {
+        CopilotEvent::Usage(data) => data.model.clone().map(|model| (model, true)),
+        CopilotEvent::Error(data) => {
+            let message = data.message.to_lowercase();
+            let rejected = [
+                "not available",
+                "not enabled",
+                "not supported",
+                "no access",
+                "not allowed",
+            ]
+            .iter()
+            .any(|phrase| message.contains(phrase));
+            if !rejected {
+                return None;
+            }
+            // Longest id first so `gpt-5-mini` isn't reported as `gpt-5`
+            let mut ids: Vec<&str> = known.iter().map(|m| m.id.as_str()).collect();
+            ids.sort_by_key(|id| std::cmp::Reverse(id.len()));
+            ids.into_iter()
+                .find(|id| message.contains(id))
+                .map(|id| (id.to_string(), false))
+        }
+        _ => None,
+    }
+}
+
+/// Model set with `/model` in the CLI, as saved in `~/.copilot/config.json`
+pub fn cli_default_model() -> Option<String> {
+    let path = dirs::home_dir()?.join(".copilot").join("config.json");
+    let contents = std::fs::read_to_string(path).ok()?;
+    let config: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    config.get("model").and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+/// Display details of a model id; ids this app doesn't know get a name and provider
+/// derived from the id itself.
+pub fn describe(id: &str) -> ModelInfo {
+    if let Some((_, name, provider, description, multiplier)) =
+        KNOWN_MODELS.iter().find(|m| m.0 == id)
+    {
+        return ModelInfo {
+            id: id.to_string(),
+            name: name.to_string(),
+            provider: provider.to_string(),
+            description: description.to_string(),
+            premium_multiplier: *multiplier,
+            available: None,
+        };
+    }
+
+    let provider = match id.split('-').next().unwrap_or_default() {
+        "claude" => "Anthropic",
+        "gpt" | "o1" | "o3" | "o4" => "OpenAI",
+        "gemini" => "Google",
+        "grok" => "xAI",
+        _ => "Other",
+    };
+    ModelInfo {
+        id: id.to_string(),
+        name: display_name(id),
+        provider: provider.to_string(),
+        description: String::new(),
+        premium_multiplier: None,
+        available: None,
+    }
+}
+
+/// `gpt-5.1-codex-mini` -> `GPT-5.1 Codex Mini`
+fn display_name(id: &str) -> String {
+    let mut name = String::new();
+    for part in id.split('-') {
+        let word = match part {
+            "gpt" => "GPT".to_string(),
+            _ => {
+                let mut chars = part.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect(),
+                    None => continue,
+                }
+            }
+        };
+        // Version numbers attach to the family name: GPT-5, but Claude Sonnet 4.5
+        if name == "GPT" {
+            name.push('-');
+        } else if !name.is_empty() {
+            name.push(' ');
+        }
+        name.push_str(&word);
+    }
+    name
+}
+
+/// The `choices` of the `--model` option in the CLI's help text, which may wrap
+/// over several lines.
+pub fn parse_model_choices(help: &str) -> Vec<String> {
+    let Some(start) = help.find("--model") else {
+        return Vec::new();
+    };
+    let rest = &help[start..];
+    let Some(open) = rest.find("(choices:") else {
+        return Vec::new();
+    };
+    // The choices belong to the next option if another flag starts first
+    if rest[..open]
+        .lines()
+        .skip(1)
+        .any(|line| line.trim_start().starts_with('-'))
+    {
+        return Vec::new();
+    }
+    let choices = &rest[open + "(choices:".len()..];
+    let end = choices.find(')').unwrap_or(choices.len());
+    choices[..end]
+        .split(',')
+        .map(|choice| choice.trim().trim_matches('"').to_string())
+        .filter(|choice| !choice.is_empty())
+        .collect()
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn load_cache(path: &Path) -> ModelCache {
+    match std::fs::read_to_string(path) {
+        Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+        Err(_) => ModelCache::default(),
+    }
+}
+
+fn save_cache(path: &Path, cache: &ModelCache) -> Result<(), std::io::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let data = serde_json::to_string_pretty(cache).map_err(std::io::Error::other)?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::events::parse_event_line;
+
+    const HELP: &str = r#"Usage: copilot [options] [command]
+
+Options:
+  --banner                    Show the startup banner
+  --model <model>             Set the AI model to use (choices: "claude-sonnet-4.5",
+                              "claude-haiku-4.5", "gpt-5", "gpt-5-mini", "grok-code-fast-1")
+  --no-color                  Disable all color output (choices: "always", "never")
+"#;
+
+    #[test]
+    fn test_parse_model_choices() {
+        assert_eq!(
+            parse_model_choices(HELP),
+            [
+                "claude-sonnet-4.5",
+                "claude-haiku-4.5",
+                "gpt-5",
+                "gpt-5-mini",
+                "grok-code-fast-1"
+            ]
+        );
+        assert!(
+            parse_model_choices("  --model <model>  Set the model\n  --x (choices: \"a\")")
+                .is_empty()
+        );
+        assert!(parse_model_choices("Usage: copilot").is_empty());
+    }
+
+    #[test]
+    fn test_describe_unknown_model() {
+        let model = describe("grok-code-fast-1");
+        assert_eq!(
+            (model.name.as_str(), model.provider.as_str()),
+            ("Grok Code Fast 1", "xAI")
+        );
+        assert_eq!(model.premium_multiplier, None);
+        assert_eq!(describe("gpt-6-mini").name, "GPT-6 Mini");
+        assert_eq!(describe("gpt-5-mini").premium_multiplier, Some(0.0));
+    }
+
+    #[test]
+    fn test_cache_is_keyed_on_cli_version() {
+        let probe = ModelProbe {
+            cli_version: "0.0.354".to_string(),
+            fetched_at: 1_000,
+            models: vec!["gpt-5".to_string()],
+        };
+        assert!(probe.is_fresh("0.0.354", 1_000 + CACHE_TTL_SECS - 1));
+        assert!(!probe.is_fresh("0.0.355", 1_000));
+        assert!(!probe.is_fresh("0.0.354", 1_000 + CACHE_TTL_SECS));
+    }
+
+    #[test]
+    fn test_availability_is_tracked_per_account() {
+        let account = |host: &str, login: &str| Account {
+            host: host.to_string(),
+            login: login.to_string(),
+            email: None,
+        };
+        let octocat = account("https://github.com", "octocat");
+        let catalog = ModelCatalog::with_cache(None);
+        let known = catalog.list(None, Some(&octocat), false);
+        let error = parse_event_line(
+            r#"{"type":"session.error","data":{"message":"Model gpt-5-mini is not available for your plan"}}"#,
+        )
+        .unwrap();
+        let (model, available) = availability_from_event(&error.event, &known).unwrap();
+        assert_eq!((model.as_str(), available), ("gpt-5-mini", false));
+
+        assert!(catalog.set_available(&octocat, &model, available));
+        assert!(!catalog.set_available(&account("https://github.com/", "octocat"), &model, false));
+        let listed = catalog.list(None, Some(&octocat), false);
+        assert_eq!(
+            listed
+                .iter()
+                .find(|m| m.id == "gpt-5-mini")
+                .unwrap()
+                .available,
+            Some(false)
+        );
+        assert_eq!(
+            listed.iter().find(|m| m.id == "gpt-5").unwrap().available,
+            None
+        );
+        let other = catalog.list(None, Some(&account("https://github.com", "hubot")), false);
+        assert!(other.iter().all(|m| m.available.is_none()));
+        // The same login on a GHES host is another account
+        let enterprise = account("https://github.example.com", "octocat");
+        let other = catalog.list(None, Some(&enterprise), false);
+        assert!(other.iter().all(|m| m.available.is_none()));
+    }
+}
@@ -1,106 +1,302 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use semver::Version;
+use serde::Deserialize;
+
 use crate::pty::PtyManager;
-use crate::types::PluginInfo;
+use crate::types::{AppError, PluginInfo};
+
+/// Manifest read from each installed plugin's directory
+const MANIFEST: &str = "plugin.json";
+/// How long fetching a remote registry index may take
+const FETCH_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// A plugin as an installed `plugin.json` or a registry index entry describes it
+#[derive(Debug, Deserialize)]
+struct PluginManifest {
+    name: String,
+    version: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    author: Option<PluginAuthor>,
+    #[serde(default)]
+    category: Option<String>,
+    #[serde(default)]
+    downloads: Option<u64>,
+}
+
+/// `author` is either a name or an npm-style `{ "name": ... }` object
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum PluginAuthor {
+    Name(String),
+    Person { name: String },
+}
+
+/// A registry index: `{ "plugins": [...] }`, or just the array
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RegistryIndex {
+    Wrapped { plugins: Vec<PluginManifest> },
+    Bare(Vec<PluginManifest>),
+}
+
+impl PluginManifest {
+    fn into_info(self, installed: bool, update_available: bool) -> PluginInfo {
+        PluginInfo {
+            name: self.name,
+            version: self.version,
+            description: self.description,
+            author: match self.author {
+                Some(PluginAuthor::Name(name)) | Some(PluginAuthor::Person { name }) => name,
+                None => String::new(),
+            },
+            installed,
+            update_available,
+            category: self.category,
+            downloads: self.downloads,
+        }
+    }
+}
 
 pub struct PluginManager;
 
 impl PluginManager {
-    /// Returns hardcoded sample data for the marketplace.
-    pub fn list_available_plugins() -> Vec<PluginInfo> {
-        vec![
-            PluginInfo {
-                name: "github-pr-review".into(),
-                version: "1.2.0".into(),
-                description: "Review pull requests directly from Copilot with inline comments and approval workflows.".into(),
-                author: "GitHub".into(),
-                installed: false,
-                update_available: false,
-                category: Some("Integrations".into()),
-                downloads: Some(45_200),
-            },
-            PluginInfo {
-                name: "docker-compose".into(),
-                version: "0.9.1".into(),
-                description: "Manage Docker Compose services, build images, and inspect containers.".into(),
-                author: "Docker Inc.".into(),
-                installed: false,
-                update_available: false,
-                category: Some("Tools".into()),
-                downloads: Some(32_100),
-            },
-            PluginInfo {
-                name: "python-debugger".into(),
-                version: "2.0.3".into(),
-                description: "Enhanced Python debugging with breakpoints, variable inspection, and step execution.".into(),
-                author: "PyTools".into(),
-                installed: false,
-                update_available: false,
-                category: Some("Languages".into()),
-                downloads: Some(28_750),
-            },
-            PluginInfo {
-                name: "sql-assistant".into(),
-                version: "1.1.0".into(),
-                description: "Write, optimize, and explain SQL queries with schema-aware suggestions.".into(),
-                author: "DataTools".into(),
-                installed: false,
-                update_available: false,
-                category: Some("Languages".into()),
-                downloads: Some(19_400),
-            },
-            PluginInfo {
-                name: "kubernetes-manager".into(),
-                version: "0.8.0".into(),
-                description: "Deploy, monitor, and troubleshoot Kubernetes clusters and workloads.".into(),
-                author: "CloudNative".into(),
-                installed: false,
-                update_available: false,
-                category: Some("Tools".into()),
-                downloads: Some(15_300),
-            },
-            PluginInfo {
-                name: "figma-bridge".into(),
-                version: "1.0.2".into(),
-                description: "Import Figma designs and generate React components from design tokens.".into(),
-                author: "DesignOps".into(),
-                installed: false,
-                update_available: false,
-                category: Some("Integrations".into()),
-                downloads: Some(12_800),
-            },
-        ]
+    /// Where installed plugins are read from, one subdirectory with a `plugin.json`
+    /// per plugin: the configured `plugins_dir`, else `~/.copilot/plugins`. The default
+    /// is where the app expects the CLI to install plugins; set `plugins_dir` when a
+    /// CLI keeps them elsewhere.
+    pub fn plugins_dir(configured: Option<&str>) -> PathBuf {
+        match configured.filter(|dir| !dir.is_empty()) {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+                home.join(".copilot").join("plugins")
+            }
+        }
     }
 
-    /// Returns sample installed plugins.
-    pub fn list_installed_plugins() -> Vec<PluginInfo> {
-        vec![
-            PluginInfo {
-                name: "github-pr-review".into(),
-                version: "1.1.0".into(),
-                description: "Review pull requests directly from Copilot with inline comments and approval workflows.".into(),
-                author: "GitHub".into(),
-                installed: true,
-                update_available: true,
-                category: Some("Integrations".into()),
-                downloads: Some(45_200),
-            },
-        ]
+    /// Plugins installed in `plugins_dir`, each flagged when the registry at `registry`
+    /// has a newer version. Without a registry, or when it can't be read, none are flagged.
+    pub async fn list_installed_plugins(
+        registry: Option<&str>,
+        plugins_dir: &Path,
+    ) -> Vec<PluginInfo> {
+        let index = match registry {
+            Some(source) => read_index(source).await.unwrap_or_else(|e| {
+                log::warn!("{e}");
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        installed_plugins(read_installed(plugins_dir), &index)
+    }
+
+    /// Plugins offered by the registry index at `registry`, a URL or a local file,
+    /// flagged as installed when `plugins_dir` has them
+    pub async fn list_available_plugins(
+        registry: Option<&str>,
+        plugins_dir: &Path,
+    ) -> Result<Vec<PluginInfo>, AppError> {
+        let source = registry.ok_or_else(|| {
+            AppError::Other("No plugin registry configured; set one in Settings".to_string())
+        })?;
+        let index = read_index(source).await?;
+        Ok(available_plugins(index, &read_installed(plugins_dir)))
     }
 
     /// Sends `/plugin install {name}` to the active PTY session.
-    pub fn install_plugin(pty: &PtyManager, session_id: &str, name: &str) -> Result<(), crate::types::AppError> {
+    pub fn install_plugin(pty: &PtyManager, session_id: &str, name: &str) -> Result<(), AppError> {
         let cmd = format!("/plugin install {}", name);
         pty.write_to_session(session_id, &cmd)
     }
 
     /// Sends `/plugin uninstall {name}` to the active PTY session.
-    pub fn uninstall_plugin(pty: &PtyManager, session_id: &str, name: &str) -> Result<(), crate::types::AppError> {
+    pub fn uninstall_plugin(pty: &PtyManager, session_id: &str, name: &str) -> Result<(), AppError> {
         let cmd = format!("/plugin uninstall {}", name);
         pty.write_to_session(session_id, &cmd)
     }
 
     /// Sends `/plugin update {name}` to the active PTY session.
-    pub fn update_plugin(pty: &PtyManager, session_id: &str, name: &str) -> Result<(), crate::types::AppError> {
+    pub fn update_plugin(pty: &PtyManager, session_id: &str, name: &str) -> Result<(), AppError> {
         let cmd = format!("/plugin update {}", name);
         pty.write_to_session(session_id, &cmd)
     }
 }
+
+/// Manifests of the plugins installed in `dir`, sorted by name. Directories without
+/// a readable `plugin.json` are skipped.
+fn read_installed(dir: &Path) -> Vec<PluginManifest> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut plugins: Vec<PluginManifest> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let path = entry.path().join(MANIFEST);
+            let text = std::fs::read_to_string(&path).ok()?;
+            serde_json::from_str(&text)
+                .map_err(|e| log::warn!("Invalid plugin manifest {}: {e}", path.display()))
+                .ok()
+        })
+        .collect();
+    plugins.sort_by(|a, b| a.name.cmp(&b.name));
+    plugins
+}
+
+/// The registry index at `source`: an `http(s)://` URL, or a path to a local file
+async fn read_index(source: &str) -> Result<Vec<PluginManifest>, AppError> {
+    let text = if source.starts_with("https://") || source.starts_with("http://") {
+        fetch(source)
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to fetch plugin registry: {e}")))?
+    } else {
+        let path = source.strip_prefix("file://").unwrap_or(source);
+        tokio::fs::read_to_string(path)
+            .await
+            .map_err(|e| AppError::Other(format!("Failed to read plugin registry {path}: {e}")))?
+    };
+    parse_index(&text)
+}
+
+async fn fetch(url: &str) -> Result<String, reqwest::Error> {
+    // reqwest is built without a crypto provider; this is the one the updater installs
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let client = reqwest::Client::builder().timeout(FETCH_TIMEOUT).build()?;
+    client
+        .get(url)
+        .send()
+        .await?
+        .error_for_status()?
+        .text()
+        .await
+}
+
+fn parse_index(text: &str) -> Result<Vec<PluginManifest>, AppError> {
+    let index: RegistryIndex = serde_json::from_str(text)
+        .map_err(|e| AppError::Other(format!("Invalid plugin registry index: {e}")))?;
+    Ok(match index {
+        RegistryIndex::Wrapped { plugins } | RegistryIndex::Bare(plugins) => plugins,
+    })
+}
+
+fn installed_plugins(installed: Vec<PluginManifest>, index: &[PluginManifest]) -> Vec<PluginInfo> {
+    installed
+        .into_iter()
+        .map(|plugin| {
+            let update_available = index
+                .iter()
+                .any(|p| p.name == plugin.name && is_newer(&p.version, &plugin.version));
+            plugin.into_info(true, update_available)
+        })
+        .collect()
+}
+
+fn available_plugins(index: Vec<PluginManifest>, installed: &[PluginManifest]) -> Vec<PluginInfo> {
+    index
+        .into_iter()
+        .map(|plugin| {
+            let current = installed.iter().find(|p| p.name == plugin.name);
+            let update_available = current.is_some_and(|p| is_newer(&plugin.version, &p.version));
+            plugin.into_info(current.is_some(), update_available)
+        })
+        .collect()
+}
+
+/// Whether `candidate` is a later semver version than `current`. Versions that
+/// don't parse, even with a leading `v` dropped, are never newer.
+fn is_newer(candidate: &str, current: &str) -> bool {
+    let parse = |v: &str| Version::parse(v.trim().trim_start_matches('v')).ok();
+    match (parse(candidate), parse(current)) {
+        (Some(candidate), Some(current)) => candidate > current,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("copilot-plugins-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_is_newer() {
+        assert!(is_newer("1.2.0", "1.1.9"));
+        assert!(is_newer("v2.0.0", "1.10.0"));
+        assert!(is_newer("1.0.0", "1.0.0-beta.2"));
+        assert!(!is_newer("1.0.0", "1.0.0"));
+        assert!(is_newer("1.10.0", "1.9.0-rc.1"));
+        assert!(!is_newer("latest", "1.0.0"));
+    }
+
+    #[test]
+    fn test_registry_from_local_file() {
+        let dir = temp_dir();
+        let plugins = dir.join("plugins");
+        for (name, manifest) in [
+            (
+                "pr-review",
+                r#"{ "name": "pr-review", "version": "1.1.0", "author": { "name": "GitHub" } }"#,
+            ),
+            (
+                "sql",
+                r#"{ "name": "sql", "version": "2.0.0", "description": "SQL" }"#,
+            ),
+            ("broken", "{"),
+        ] {
+            std::fs::create_dir_all(plugins.join(name)).unwrap();
+            std::fs::write(plugins.join(name).join(MANIFEST), manifest).unwrap();
+        }
+        let index = dir.join("index.json");
+        std::fs::write(
+            &index,
+            r#"{ "plugins": [
+                { "name": "pr-review", "version": "1.2.0", "author": "GitHub", "downloads": 45200 },
+                { "name": "sql", "version": "1.9.0" },
+                { "name": "docker", "version": "0.9.1", "category": "Tools" }
+            ] }"#,
+        )
+        .unwrap();
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let index = runtime
+            .block_on(read_index(index.to_str().unwrap()))
+            .unwrap();
+        let installed = read_installed(&plugins);
+        assert_eq!(installed.len(), 2);
+
+        let available = available_plugins(index, &installed);
+        let flags: Vec<_> = available
+            .iter()
+            .map(|p| (p.name.as_str(), p.installed, p.update_available))
+            .collect();
+        assert_eq!(
+            flags,
+            [
+                ("pr-review", true, true),
+                ("sql", true, false),
+                ("docker", false, false)
+            ]
+        );
+
+        let index = runtime
+            .block_on(read_index(&format!(
+                "file://{}",
+                dir.join("index.json").display()
+            )))
+            .unwrap();
+        let installed = installed_plugins(read_installed(&plugins), &index);
+        assert_eq!(installed[0].author, "GitHub");
+        assert!(installed[0].update_available);
+        assert!(!installed[1].update_available);
+
+        assert!(runtime.block_on(read_index("/no/such/index.json")).is_err());
+        assert!(parse_index(r#"[{ "name": "bare", "version": "1.0.0" }]"#).is_ok());
+    }
+}
@@ -0,0 +1,329 @@
+use std::fmt;
+use std::io::Read;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::AppError;
+
+/// How long `copilot --version` and `--help` may take before the binary is given up on
+const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+const PROBE_POLL_INTERVAL: Duration = Duration::from_millis(25);
+
+/// CLI flags the app relies on that older releases don't have. Whether a binary has
+/// one is read from its `--help`, not guessed from its version.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CliFeature {
+    /// `--allow-tool` / `--deny-tool`
+    ToolPermissions,
+    /// `--add-dir`
+    AddDir,
+    /// `--resume <session-id>`
+    Resume,
+    /// `--allow-all-paths`
+    AllowAllPaths,
+    /// `--additional-mcp-config`, for project MCP servers and resolved secrets
+    AdditionalMcpConfig,
+}
+
+impl CliFeature {
+    pub const ALL: [CliFeature; 5] = [
+        CliFeature::ToolPermissions,
+        CliFeature::AddDir,
+        CliFeature::Resume,
+        CliFeature::AllowAllPaths,
+        CliFeature::AdditionalMcpConfig,
+    ];
+
+    /// Features needed to run the CLI with `args`.
+    pub fn used_by(args: &[String]) -> Vec<CliFeature> {
+        CliFeature::ALL
+            .into_iter()
+            .filter(|feature| {
+                args.iter().any(|arg| {
+                    arg == feature.flag()
+                        || (*feature == CliFeature::ToolPermissions && arg == "--deny-tool")
+                })
+            })
+            .collect()
+    }
+
+    pub fn flag(self) -> &'static str {
+        match self {
+            CliFeature::ToolPermissions => "--allow-tool",
+            CliFeature::AddDir => "--add-dir",
+            CliFeature::Resume => "--resume",
+            CliFeature::AllowAllPaths => "--allow-all-paths",
+            CliFeature::AdditionalMcpConfig => "--additional-mcp-config",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct CliVersion {
+    pub major: u32,
+    pub minor: u32,
+    pub patch: u32,
+}
+
+impl CliVersion {
+    pub const fn new(major: u32, minor: u32, patch: u32) -> Self {
+        Self {
+            major,
+            minor,
+            patch,
+        }
+    }
+
+    /// The version in `copilot --version` output, e.g. `0.0.354` from
+    /// `0.0.354\nCommit: 1a2b3c`. Pre-release suffixes are ignored.
+    pub fn parse(output: &str) -> Option<Self> {
+        output.split_whitespace().find_map(|word| {
+            let word = word.trim_start_matches('v');
+            let core = word.split(['-', '+']).next()?;
+            let mut parts = core.split('.').map(|p| p.parse::<u32>());
+            let major = parts.next()?.ok()?;
+            let minor = parts.next()?.ok()?;
+            let patch = parts.next().unwrap_or(Ok(0)).ok()?;
+            Some(Self::new(major, minor, patch))
+        })
+    }
+}
+
+impl fmt::Display for CliVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// The Copilot binary used for new sessions
+#[derive(Debug, Clone)]
+pub struct CopilotCli {
+    pub path: String,
+    /// `None` when `--version` couldn't be read
+    pub version: Option<CliVersion>,
+    /// Long flags listed by `--help`; `None` when it couldn't be read, and no feature
+    /// is gated then
+    pub flags: Option<Vec<String>>,
+}
+
+impl CopilotCli {
+    /// Read the version and flags of the binary at `path`.
+    pub fn probe(path: String) -> Self {
+        Self {
+            version: detect_version(&path),
+            flags: run(&path, &["--help"]).map(|help| parse_flags(&help)),
+            path,
+        }
+    }
+
+    pub fn supports(&self, feature: CliFeature) -> bool {
+        match &self.flags {
+            Some(flags) => flags.iter().any(|f| f == feature.flag()),
+            None => true,
+        }
+    }
+
+    /// Every new session passes `--add-dir`, so a CLI without it can't run one.
+    pub fn is_supported(&self) -> bool {
+        self.supports(CliFeature::AddDir)
+    }
+
+    /// Error naming the first of `features` this CLI lacks.
+    pub fn require(&self, features: &[CliFeature]) -> Result<(), AppError> {
+        match features.iter().find(|f| !self.supports(**f)) {
+            Some(feature) => Err(AppError::Other(format!(
+                "Copilot CLI {} does not support {}; update the CLI to use it",
+                self.version.map_or_else(|| self.path.clone(), |v| v.to_string()),
+                feature.flag(),
+            ))),
+            None => Ok(()),
+        }
+    }
+
+    pub fn unsupported_features(&self) -> Vec<CliFeature> {
+        CliFeature::ALL
+            .into_iter()
+            .filter(|feature| !self.supports(*feature))
+            .collect()
+    }
+}
+
+/// Long flags in `--help` output, e.g. `--add-dir` from `  --add-dir <directory>`.
+pub fn parse_flags(help: &str) -> Vec<String> {
+    let mut flags: Vec<String> = help
+        .split(|c: char| c.is_whitespace() || c == ',')
+        .filter_map(|word| {
+            let name = word.strip_prefix("--")?;
+            let len = name
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-'))
+                .unwrap_or(name.len());
+            (len > 0).then(|| format!("--{}", &name[..len]))
+        })
+        .collect();
+    flags.sort();
+    flags.dedup();
+    flags
+}
+
+/// Find the binary to use: the configured path or command name, else `copilot` on PATH.
+/// A configured binary that can't be found is an error rather than a reason to fall
+/// back to PATH, so a broken `copilot_path` isn't hidden behind another CLI.
+pub fn resolve(configured: Option<&str>) -> Result<Option<String>, AppError> {
+    match configured.filter(|c| !c.is_empty()) {
+        Some(configured) => which::which(configured)
+            .map(|path| Some(path.to_string_lossy().to_string()))
+            .map_err(|e| {
+                AppError::Other(format!(
+                    "Configured Copilot CLI {} is not usable: {}",
+                    configured, e
+                ))
+            }),
+        None => Ok(which::which("copilot")
+            .ok()
+            .map(|p| p.to_string_lossy().to_string())),
+    }
+}
+
+/// `resolve` the binary and probe it.
+pub fn detect(configured: Option<&str>) -> Result<Option<CopilotCli>, AppError> {
+    resolve(configured).map(|path| path.map(CopilotCli::probe))
+}
+
+/// Check that `path` is a Copilot CLI this app supports.
+pub fn validate(path: &str) -> Result<CopilotCli, AppError> {
+    let resolved = which::which(path)
+        .map_err(|e| AppError::Other(format!("{} is not an executable: {}", path, e)))?;
+    let resolved = resolved.to_string_lossy().to_string();
+    let output = run(&resolved, &["--version"])
+        .ok_or_else(|| AppError::Other(format!("{} --version failed or timed out", path)))?;
+    let version = CliVersion::parse(&output).ok_or_else(|| {
+        AppError::Other(format!("{} did not report a version; is it the Copilot CLI?", path))
+    })?;
+    let cli = CopilotCli {
+        version: Some(version),
+        flags: run(&resolved, &["--help"]).map(|help| parse_flags(&help)),
+        path: resolved,
+    };
+    if !cli.is_supported() {
+        return Err(AppError::Other(format!(
+            "Copilot CLI {} is too old: it has no {} flag",
+            version,
+            CliFeature::AddDir.flag()
+        )));
+    }
+    Ok(cli)
+}
+
+pub fn detect_version(path: &str) -> Option<CliVersion> {
+    run(path, &["--version"]).and_then(|output| CliVersion::parse(&output))
+}
+
+/// Stdout of a successful `path args...` run, or `None` if it fails or outlives
+/// `PROBE_TIMEOUT`.
+pub fn run(path: &str, args: &[&str]) -> Option<String> {
+    run_with_timeout(path, args, PROBE_TIMEOUT)
+}
+
+fn run_with_timeout(path: &str, args: &[&str], timeout: Duration) -> Option<String> {
+    let mut child = Command::new(Path::new(path))
+        .args(args)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .spawn()
+        .ok()?;
+    // Read on another thread so a chatty child can't block on a full pipe
+    let mut stdout = child.stdout.take()?;
+    let reader = std::thread::spawn(move || {
+        let mut output = String::new();
+        let _ = stdout.read_to_string(&mut output);
+        output
+    });
+
+    let deadline = Instant::now() + timeout;
+    let status = loop {
+        match child.try_wait() {
+            Ok(Some(status)) => break status,
+            Ok(None) if Instant::now() < deadline => std::thread::sleep(PROBE_POLL_INTERVAL),
+            _ => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return None;
+            }
+        }
+    };
+    let output = reader.join().ok()?;
+    status.success().then_some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version() {
+        assert_eq!(CliVersion::parse("0.0.354\nCommit: 1a2b3c\n"), Some(CliVersion::new(0, 0, 354)));
+        assert_eq!(CliVersion::parse("GitHub Copilot CLI v1.2.0-beta.1"), Some(CliVersion::new(1, 2, 0)));
+        assert_eq!(CliVersion::parse("unknown"), None);
+    }
+
+    #[test]
+    fn test_parse_flags() {
+        let help = "Usage: copilot [options]\n\n\
+            Options:\n  \
+            -v, --version                Show version\n  \
+            --add-dir <directory>        Add a directory to the allowed list\n  \
+            --allow-tool [tools...]      Allow tools without asking\n  \
+            --log-level=<level>          Set the log level\n  \
+            -h, --help                   Display help (see --help)\n";
+        assert_eq!(
+            parse_flags(help),
+            ["--add-dir", "--allow-tool", "--help", "--log-level", "--version"]
+        );
+    }
+
+    #[test]
+    fn test_feature_gating() {
+        let old = CopilotCli {
+            path: "copilot".to_string(),
+            version: Some(CliVersion::new(0, 0, 331)),
+            flags: Some(["--add-dir", "--allow-tool", "--deny-tool"].map(String::from).into()),
+        };
+        assert!(old.is_supported());
+        assert!(old.require(&[CliFeature::AddDir]).is_ok());
+        let err = old.require(&[CliFeature::AddDir, CliFeature::Resume]).unwrap_err();
+        assert!(err.to_string().contains("--resume"));
+        assert_eq!(
+            old.unsupported_features(),
+            [CliFeature::Resume, CliFeature::AllowAllPaths, CliFeature::AdditionalMcpConfig]
+        );
+
+        let args: Vec<String> = ["--deny-tool", "shell", "--resume", "abc"].map(String::from).into();
+        assert_eq!(CliFeature::used_by(&args), [CliFeature::ToolPermissions, CliFeature::Resume]);
+
+        let unknown = CopilotCli { flags: None, ..old };
+        assert!(unknown.require(&[CliFeature::Resume]).is_ok());
+        assert!(unknown.unsupported_features().is_empty());
+    }
+
+    #[test]
+    fn test_configured_path_does_not_fall_back() {
+        let err = resolve(Some("/nonexistent/copilot")).unwrap_err();
+        assert!(err.to_string().contains("/nonexistent/copilot"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_times_out() {
+        assert_eq!(run("sh", &["-c", "echo 0.0.400"]).as_deref(), Some("0.0.400\n"));
+        assert_eq!(run("sh", &["-c", "exit 1"]), None);
+        let started = Instant::now();
+        let timeout = Duration::from_millis(200);
+        assert_eq!(run_with_timeout("sh", &["-c", "sleep 30"], timeout), None);
+        assert!(started.elapsed() < Duration::from_secs(2));
+    }
+}
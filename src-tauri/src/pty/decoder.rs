@@ -0,0 +1,90 @@
+/// Incremental UTF-8 decoder for PTY chunks.
+///
+/// A read from the PTY can end in the middle of a multi-byte character; instead of
+/// turning each half into U+FFFD, the incomplete tail is held back until the next chunk.
+pub struct Utf8Decoder {
+    pending: Vec<u8>,
+}
+
+impl Utf8Decoder {
+    pub fn new() -> Self {
+        Self { pending: Vec::new() }
+    }
+
+    /// Decode as much of `bytes` (plus any held-back tail) as forms complete characters.
+    /// Genuinely invalid bytes are replaced with U+FFFD.
+    pub fn decode(&mut self, bytes: &[u8]) -> String {
+        self.pending.extend_from_slice(bytes);
+
+        let mut out = String::with_capacity(self.pending.len());
+        let mut input = &self.pending[..];
+        loop {
+            match std::str::from_utf8(input) {
+                Ok(valid) => {
+                    out.push_str(valid);
+                    input = &[];
+                    break;
+                }
+                Err(e) => {
+                    let (valid, rest) = input.split_at(e.valid_up_to());
+                    out.push_str(std::str::from_utf8(valid).unwrap_or_default());
+                    match e.error_len() {
+                        Some(len) => {
+                            out.push(char::REPLACEMENT_CHARACTER);
+                            input = &rest[len..];
+                        }
+                        None => {
+                            // Incomplete sequence at the end: wait for more bytes
+                            input = rest;
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+
+        let remaining = input.to_vec();
+        self.pending = remaining;
+        out
+    }
+
+    /// Flush a held-back incomplete sequence (call on stream end).
+    pub fn finish(&mut self) -> String {
+        let pending = std::mem::take(&mut self.pending);
+        String::from_utf8_lossy(&pending).into_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ascii_passthrough() {
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.decode(b"\x1b[32mok\x1b[0m"), "\x1b[32mok\x1b[0m");
+        assert_eq!(decoder.finish(), "");
+    }
+
+    #[test]
+    fn test_split_multibyte_character() {
+        let mut decoder = Utf8Decoder::new();
+        let bytes = "⚡ go".as_bytes(); // '⚡' is three bytes
+        assert_eq!(decoder.decode(&bytes[..1]), "");
+        assert_eq!(decoder.decode(&bytes[1..2]), "");
+        assert_eq!(decoder.decode(&bytes[2..]), "⚡ go");
+    }
+
+    #[test]
+    fn test_invalid_bytes_replaced() {
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.decode(b"a\xffb"), "a\u{FFFD}b");
+    }
+
+    #[test]
+    fn test_finish_flushes_incomplete_tail() {
+        let mut decoder = Utf8Decoder::new();
+        assert_eq!(decoder.decode(&"é".as_bytes()[..1]), "");
+        assert_eq!(decoder.finish(), "\u{FFFD}");
+    }
+}
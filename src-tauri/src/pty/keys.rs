@@ -0,0 +1,57 @@
+use crate::types::{KeyInput, NamedKey};
+
+/// Terminal byte sequence for a named key (xterm conventions, normal cursor mode).
+pub fn key_bytes(key: NamedKey) -> &'static [u8] {
+    match key {
+        NamedKey::Enter => b"\r",
+        NamedKey::Tab => b"\t",
+        NamedKey::Esc => b"\x1b",
+        NamedKey::Backspace => b"\x7f",
+        NamedKey::Space => b" ",
+        NamedKey::Up => b"\x1b[A",
+        NamedKey::Down => b"\x1b[B",
+        NamedKey::Right => b"\x1b[C",
+        NamedKey::Left => b"\x1b[D",
+        NamedKey::CtrlC => b"\x03",
+        NamedKey::CtrlD => b"\x04",
+    }
+}
+
+/// Flatten a `send_keys` sequence into the bytes written to the PTY.
+pub fn encode_keys(keys: &[KeyInput]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for key in keys {
+        match key {
+            KeyInput::Key(named) => bytes.extend_from_slice(key_bytes(*named)),
+            KeyInput::Text(text) => bytes.extend_from_slice(text.as_bytes()),
+            KeyInput::Bytes(raw) => bytes.extend_from_slice(raw),
+        }
+    }
+    bytes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_mixed_sequence() {
+        let keys = vec![
+            KeyInput::Key(NamedKey::Down),
+            KeyInput::Key(NamedKey::Down),
+            KeyInput::Key(NamedKey::Enter),
+            KeyInput::Text("y".to_string()),
+            KeyInput::Bytes(vec![0x03]),
+        ];
+        assert_eq!(encode_keys(&keys), b"\x1b[B\x1b[B\ry\x03".to_vec());
+    }
+
+    #[test]
+    fn test_deserialize_symbolic_keys() {
+        let keys: Vec<KeyInput> = serde_json::from_str(
+            r#"[{"type":"Key","data":"Ctrl-C"},{"type":"Key","data":"Esc"},{"type":"Text","data":"/exit"}]"#,
+        )
+        .unwrap();
+        assert_eq!(encode_keys(&keys), b"\x03\x1b/exit".to_vec());
+    }
+}
@@ -1,103 +1,359 @@
 use portable_pty::{CommandBuilder, MasterPty, NativePtySystem, PtySize, PtySystem};
 use std::collections::HashMap;
 use std::io::{Read, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, Mutex};
-use tauri::{AppHandle, Emitter};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager};
 use tokio::sync::mpsc;
 
+use super::cli::{self, CliFeature, CopilotCli};
+use super::decoder::Utf8Decoder;
+use super::keys::encode_keys;
 use super::parser::AnsiParser;
-use crate::types::{AppError, PtyEvent};
-
-struct PtySession {
+use super::permission::PermissionTracker;
+use super::profile::PermissionProfile;
+use super::scrollback::Scrollback;
+use super::supervisor::{handle_crash, ExitInfo};
+use crate::auth;
+use crate::config::{AppConfig, ShutdownTimeouts, SpawnProfile, ToolPolicy};
+use crate::mcp::{self, McpManager};
+use crate::session::EventStreams;
+use crate::usage::UsageTracker;
+use crate::types::{
+    Account, AgentMode, AppError, KeyInput, ParsedOutput, PtyEvent, SessionInfo, SessionOutput,
+};
+
+/// Polling interval while waiting for a child process to exit
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(50);
+/// How long the reaper waits for an exit status once the terminal has closed
+const REAP_TIMEOUT: Duration = Duration::from_secs(1);
+/// Terminal size a session starts with until the frontend reports its own
+const DEFAULT_PTY_SIZE: PtySize = PtySize {
+    rows: 50,
+    cols: 120,
+    pixel_width: 0,
+    pixel_height: 0,
+};
+
+type SessionMap = Arc<Mutex<HashMap<String, PtySession>>>;
+
+pub(super) struct PtySession {
     writer: Box<dyn Write + Send>,
     master: Box<dyn MasterPty + Send>,
     child: Box<dyn portable_pty::Child + Send + Sync>,
+    scrollback: Arc<Mutex<Scrollback>>,
+    /// Whether undecoded terminal output is emitted as `PtyEvent::Output` for a raw terminal view
+    raw_output: Arc<AtomicBool>,
+    /// Permission prompt the CLI is currently waiting on
+    permissions: Arc<Mutex<PermissionTracker>>,
+    /// Distinguishes successive processes of a session that is restarted under the same id
+    instance: u64,
+    started_at: Instant,
+    /// Consecutive automatic restarts after crashes, including this process
+    crash_restarts: u32,
+    /// Set by `shutdown_session` so the exit isn't treated as a crash
+    shutdown_requested: bool,
+    /// Last size set by `resize_pty`, reused when the process is restarted
+    size: PtySize,
+    /// Tool and file events come from `events.jsonl`; the parser's heuristics are muted
+    structured_events: Arc<AtomicBool>,
+    /// Account the process was started as, given back to it if a restart fails
+    account: Option<Account>,
     /// Sender to signal the reader task to stop
     _cancel_tx: mpsc::Sender<()>,
 }
 
-pub struct PtyManager {
-    sessions: Arc<Mutex<HashMap<String, PtySession>>>,
-    copilot_path: Arc<Mutex<Option<String>>>,
+/// Session state handed from one process to the next when the Copilot CLI is
+/// restarted, so the frontend sees one continuous stream.
+#[derive(Clone)]
+pub(super) struct Carryover {
+    pub scrollback: Arc<Mutex<Scrollback>>,
+    pub raw_output: Arc<AtomicBool>,
+    pub crash_restarts: u32,
+    pub size: PtySize,
+    pub structured_events: Arc<AtomicBool>,
 }
 
-impl PtyManager {
-    pub fn new() -> Self {
-        let copilot_path = which::which("copilot")
-            .ok()
-            .map(|p| p.to_string_lossy().to_string());
+impl Carryover {
+    fn new() -> Self {
+        Self {
+            scrollback: Arc::new(Mutex::new(Scrollback::new())),
+            raw_output: Arc::new(AtomicBool::new(false)),
+            crash_restarts: 0,
+            size: DEFAULT_PTY_SIZE,
+            structured_events: Arc::new(AtomicBool::new(false)),
+        }
+    }
+}
+
+/// How to launch the Copilot CLI for a desktop session
+#[derive(Debug, Clone)]
+pub struct SpawnOptions {
+    pub working_dir: String,
+    pub model: Option<String>,
+    pub mode: AgentMode,
+    /// Copilot CLI session to continue with `--resume`
+    pub resume_session_id: Option<String>,
+    /// User allow/deny rules layered over the mode's permission profile
+    pub tool_policy: ToolPolicy,
+    /// Environment, extra `--add-dir` roots and extra flags for the project
+    pub profile: SpawnProfile,
+    /// Account to start the CLI as; `None` keeps the CLI's active account
+    pub account: Option<Account>,
+}
 
+impl SpawnOptions {
+    /// Options that continue an existing desktop session, resuming its Copilot CLI
+    /// session when one is linked.
+    pub fn for_session(session: &SessionInfo, config: &AppConfig) -> Self {
         Self {
-            sessions: Arc::new(Mutex::new(HashMap::new())),
-            copilot_path: Arc::new(Mutex::new(copilot_path)),
+            working_dir: session.working_dir.clone(),
+            model: session.model.clone(),
+            mode: session.mode.clone(),
+            resume_session_id: session.copilot_session_id.clone(),
+            tool_policy: config.tool_policy_for(&session.working_dir),
+            profile: config.spawn_profile_for(&session.working_dir),
+            account: session.account.clone(),
         }
     }
+}
+
+/// Command-line arguments the Copilot CLI is started with for `options`.
+pub fn spawn_args(options: &SpawnOptions) -> Vec<String> {
+    let mut args = Vec::new();
+    if let Some(rid) = &options.resume_session_id {
+        args.push("--resume".to_string());
+        args.push(rid.clone());
+    } else {
+        args.push("--add-dir".to_string());
+        args.push(options.working_dir.clone());
+    }
 
-    pub fn is_copilot_installed(&self) -> bool {
-        self.copilot_path.lock().unwrap_or_else(|e| e.into_inner()).is_some()
+    if let Some(m) = &options.model {
+        args.push("--model".to_string());
+        args.push(m.clone());
+    }
+    args.extend(
+        PermissionProfile::for_mode(&options.mode)
+            .with_policy(&options.tool_policy)
+            .to_args(),
+    );
+    for dir in &options.profile.add_dirs {
+        args.push("--add-dir".to_string());
+        args.push(dir.clone());
+    }
+    args.extend(options.profile.extra_args.iter().cloned());
+    args
+}
+
+/// Hands a session its project MCP servers and resolved secrets, written by
+/// `McpManager::write_session_overrides`
+fn mcp_config_args(overrides: Option<&Path>) -> Vec<String> {
+    match overrides {
+        Some(path) => vec![
+            "--additional-mcp-config".to_string(),
+            format!("@{}", path.display()),
+        ],
+        None => Vec::new(),
+    }
+}
+
+pub struct PtyManager {
+    sessions: SessionMap,
+    copilot_path: Arc<Mutex<Option<String>>>,
+    /// Why the configured binary can't be used, while `copilot_path` is unset for it
+    copilot_error: Mutex<Option<String>>,
+    /// The binary at `copilot_path` with its version and flags, probed on first use
+    cli: Mutex<Option<CopilotCli>>,
+    next_instance: AtomicU64,
+}
+
+impl PtyManager {
+    /// `configured` is `AppConfig.copilot_path`; without it `copilot` is looked up on
+    /// PATH. A configured binary that doesn't resolve leaves no CLI and reports why.
+    pub fn new(configured: Option<&str>) -> Self {
+        let manager = Self {
+            sessions: Arc::new(Mutex::new(HashMap::new())),
+            copilot_path: Arc::new(Mutex::new(None)),
+            copilot_error: Mutex::new(None),
+            cli: Mutex::new(None),
+            next_instance: AtomicU64::new(0),
+        };
+        match cli::resolve(configured) {
+            Ok(path) => *manager.copilot_path.lock().unwrap_or_else(|e| e.into_inner()) = path,
+            Err(e) => manager.set_copilot_cli(Err(e)),
+        }
+        manager
     }
 
     pub fn get_copilot_path(&self) -> Option<String> {
         self.copilot_path.lock().unwrap_or_else(|e| e.into_inner()).clone()
     }
 
+    /// The binary new sessions run, with its version and flags.
+    pub fn copilot_cli(&self) -> Option<CopilotCli> {
+        let path = self.get_copilot_path()?;
+        let mut cli = self.cli.lock().unwrap_or_else(|e| e.into_inner());
+        Some(
+            cli.get_or_insert_with(|| CopilotCli::probe(path))
+                .clone(),
+        )
+    }
+
+    /// Why the configured Copilot CLI isn't used, if it isn't.
+    pub fn copilot_error(&self) -> Option<String> {
+        self.copilot_error.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Switch the binary used for new sessions; running sessions keep theirs. An error
+    /// leaves no binary and is reported by `copilot_error`.
+    pub fn set_copilot_cli(&self, cli: Result<Option<CopilotCli>, AppError>) {
+        let mut path = self.copilot_path.lock().unwrap_or_else(|e| e.into_inner());
+        let mut probed = self.cli.lock().unwrap_or_else(|e| e.into_inner());
+        let mut error = self.copilot_error.lock().unwrap_or_else(|e| e.into_inner());
+        match cli {
+            Ok(cli) => {
+                *path = cli.as_ref().map(|c| c.path.clone());
+                *probed = cli;
+                *error = None;
+            }
+            Err(e) => {
+                *path = None;
+                *probed = None;
+                *error = Some(e.to_string());
+            }
+        }
+    }
+
+    pub fn has_session(&self, session_id: &str) -> bool {
+        self.sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(session_id)
+    }
+
     pub fn spawn_session(
         &self,
         session_id: &str,
-        working_dir: &str,
-        model: Option<&str>,
-        mode: Option<&str>,
-        resume_session_id: Option<&str>,
+        options: &SpawnOptions,
         app_handle: AppHandle,
     ) -> Result<(), AppError> {
-        let copilot_path = self
-            .get_copilot_path()
-            .ok_or(AppError::CopilotNotFound)?;
-
-        let pty_system = NativePtySystem::default();
-        let pair = pty_system
-            .openpty(PtySize {
-                rows: 50,
-                cols: 120,
-                pixel_width: 0,
-                pixel_height: 0,
-            })
-            .map_err(|e| AppError::PtyError(e.to_string()))?;
-
-        let mut cmd = CommandBuilder::new(&copilot_path);
-        cmd.cwd(working_dir);
+        self.spawn_with_output(session_id, options, Carryover::new(), app_handle)
+            .map(|_| ())
+    }
 
-        if let Some(rid) = resume_session_id {
-            cmd.arg("--resume");
-            cmd.arg(rid);
-        } else {
-            cmd.arg("--add-dir");
-            cmd.arg(working_dir);
-        }
+    /// Replace the running Copilot process of a session with a new one, keeping its
+    /// scrollback and raw-output setting so the frontend sees one continuous stream.
+    ///
+    /// The old process is only stopped once the new one has started, so a restart that
+    /// fails leaves the session running as it was.
+    pub fn restart_session(
+        &self,
+        session_id: &str,
+        options: &SpawnOptions,
+        app_handle: AppHandle,
+    ) -> Result<(), AppError> {
+        let (carry, account) = {
+            let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+            let old = sessions
+                .get(session_id)
+                .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+            let carry = Carryover {
+                scrollback: old.scrollback.clone(),
+                raw_output: old.raw_output.clone(),
+                crash_restarts: old.crash_restarts,
+                size: old.size,
+                structured_events: old.structured_events.clone(),
+            };
+            (carry, old.account.clone())
+        };
 
-        if let Some(m) = model {
-            cmd.arg("--model");
-            cmd.arg(m);
-        }
-        if mode == Some("autopilot") {
-            cmd.arg("--allow-all-tools");
+        match self.spawn_with_output(session_id, options, carry, app_handle) {
+            Ok(Some(mut old)) => {
+                // Its reader sees the instance replaced and reports no exit, so nothing
+                // else removes its overrides
+                let _ = old.child.kill();
+                mcp::remove_session_overrides(session_id, old.instance);
+                Ok(())
+            }
+            Ok(None) => Ok(()),
+            Err(e) => {
+                if let Err(e) = auth::acquire_account(session_id, account.as_ref()) {
+                    log::warn!("Session {} keeps a changed account: {}", session_id, e);
+                }
+                Err(e)
+            }
         }
-        cmd.env("TERM", "xterm-256color");
-
-        let child = pair
-            .slave
-            .spawn_command(cmd)
-            .map_err(|e| AppError::PtyError(e.to_string()))?;
+    }
 
-        let reader = pair
-            .master
-            .try_clone_reader()
-            .map_err(|e| AppError::PtyError(e.to_string()))?;
+    /// Start the CLI for `session_id` and register it, returning the process it
+    /// replaces. On failure a process already registered under the id is left alone.
+    pub(super) fn spawn_with_output(
+        &self,
+        session_id: &str,
+        options: &SpawnOptions,
+        carry: Carryover,
+        app_handle: AppHandle,
+    ) -> Result<Option<PtySession>, AppError> {
+        let cli = self.copilot_cli().ok_or_else(|| match self.copilot_error() {
+            Some(error) => AppError::Other(error),
+            None => AppError::CopilotNotFound,
+        })?;
+        let mut args = spawn_args(options);
+        cli.require(&CliFeature::used_by(&args))?;
+        let env = options
+            .profile
+            .resolve_env(&options.working_dir)
+            .map_err(|e| AppError::Other(format!("Failed to read env file: {}", e)))?;
+
+        // The CLI signs in as the config's `last_logged_in_user` when it starts
+        auth::acquire_account(session_id, options.account.as_ref())?;
+        let instance = self.next_instance.fetch_add(1, Ordering::Relaxed);
+        let spawned = (|| {
+            // The CLI doesn't read project MCP configs, and secrets stay out of the config files
+            let mcp_overrides = app_handle.state::<McpManager>().write_session_overrides(
+                session_id,
+                instance,
+                &options.working_dir,
+                &env,
+            )?;
+            args.extend(mcp_config_args(mcp_overrides.as_deref()));
+            cli.require(&CliFeature::used_by(&args))?;
+
+            let pty_error = |e: &dyn std::fmt::Display| AppError::PtyError(e.to_string());
+            let pair = NativePtySystem::default()
+                .openpty(carry.size)
+                .map_err(|e| pty_error(&e))?;
+            let mut cmd = CommandBuilder::new(&cli.path);
+            cmd.cwd(&options.working_dir);
+            cmd.args(&args);
+            cmd.env("TERM", "xterm-256color");
+            for (key, value) in &env {
+                cmd.env(key, value);
+            }
+            if let Some(account) = &options.account {
+                for (key, value) in auth::host_env(&account.host) {
+                    cmd.env(key, value);
+                }
+            }
 
-        let writer = pair
-            .master
-            .take_writer()
-            .map_err(|e| AppError::PtyError(e.to_string()))?;
+            let child = pair.slave.spawn_command(cmd).map_err(|e| pty_error(&e))?;
+            let reader = pair.master.try_clone_reader().map_err(|e| pty_error(&e))?;
+            let writer = pair.master.take_writer().map_err(|e| pty_error(&e))?;
+            Ok::<_, AppError>((pair.master, child, reader, writer))
+        })();
+        let (master, child, reader, writer) = match spawned {
+            Ok(spawned) => spawned,
+            Err(e) => {
+                mcp::remove_session_overrides(session_id, instance);
+                if !self.has_session(session_id) {
+                    auth::release_account(session_id);
+                }
+                return Err(e);
+            }
+        };
 
         // Channel for raw PTY data; reader thread sends chunks to async task
         let (data_tx, mut data_rx) = mpsc::channel::<Vec<u8>>(256);
@@ -105,6 +361,30 @@ impl PtyManager {
         let (cancel_tx, mut cancel_rx) = mpsc::channel::<()>(1);
 
         let sid = session_id.to_string();
+        let permissions = Arc::new(Mutex::new(PermissionTracker::new()));
+
+        // Register the session before any output is processed so the reaper always finds it
+        let replaced = self.sessions.lock().unwrap_or_else(|e| e.into_inner()).insert(
+            session_id.to_string(),
+            PtySession {
+                writer,
+                master,
+                child,
+                scrollback: carry.scrollback.clone(),
+                raw_output: carry.raw_output.clone(),
+                permissions: permissions.clone(),
+                instance,
+                started_at: Instant::now(),
+                crash_restarts: carry.crash_restarts,
+                shutdown_requested: false,
+                size: carry.size,
+                structured_events: carry.structured_events.clone(),
+                account: options.account.clone(),
+                _cancel_tx: cancel_tx,
+            },
+        );
+        // The new process runs the model it was started with, not what the old one reported
+        app_handle.state::<UsageTracker>().reset_session(session_id);
 
         // Blocking reader thread: reads raw bytes from PTY and sends via channel
         std::thread::spawn({
@@ -131,8 +411,13 @@ impl PtyManager {
         // Async task: receives raw data, parses, and emits structured events
         let sid_clone = sid.clone();
         let child_sessions = self.sessions.clone();
+        let task_scrollback = carry.scrollback.clone();
+        let task_raw_output = carry.raw_output.clone();
+        let task_permissions = permissions.clone();
+        let task_structured = carry.structured_events.clone();
         tauri::async_runtime::spawn(async move {
             let mut parser = AnsiParser::new();
+            let mut decoder = Utf8Decoder::new();
             let event_name = format!("pty-output-{}", sid_clone);
 
             loop {
@@ -140,51 +425,85 @@ impl PtyManager {
                     chunk = data_rx.recv() => {
                         match chunk {
                             Some(bytes) => {
-                                let text = String::from_utf8_lossy(&bytes);
+                                lock_scrollback(&task_scrollback).append_raw(&bytes);
+                                let text = decoder.decode(&bytes);
+                                if text.is_empty() {
+                                    continue;
+                                }
+                                if task_raw_output.load(Ordering::Relaxed) {
+                                    emit_event(
+                                        &app_handle,
+                                        &event_name,
+                                        &task_scrollback,
+                                        PtyEvent::Output(text.clone()),
+                                    );
+                                }
                                 let parsed_events = parser.feed(&text);
                                 for parsed in parsed_events {
-                                    let _ = app_handle.emit(
+                                    emit_parsed(
+                                        &app_handle,
                                         &event_name,
-                                        PtyEvent::Parsed(parsed),
+                                        &task_scrollback,
+                                        &task_permissions,
+                                        &task_structured,
+                                        parsed,
                                     );
                                 }
                             }
                             None => {
                                 // Reader closed — flush remaining buffered content
-                                let remaining = parser.flush();
+                                let tail = decoder.finish();
+                                if !tail.is_empty() && task_raw_output.load(Ordering::Relaxed) {
+                                    emit_event(
+                                        &app_handle,
+                                        &event_name,
+                                        &task_scrollback,
+                                        PtyEvent::Output(tail.clone()),
+                                    );
+                                }
+                                let mut remaining = parser.feed(&tail);
+                                remaining.extend(parser.flush());
                                 for parsed in remaining {
-                                    let _ = app_handle.emit(
+                                    emit_parsed(
+                                        &app_handle,
                                         &event_name,
-                                        PtyEvent::Parsed(parsed),
+                                        &task_scrollback,
+                                        &task_permissions,
+                                        &task_structured,
+                                        parsed,
                                     );
                                 }
 
-                                // Detect exit code from child process
-                                let exit_code = {
-                                    let mut sessions = child_sessions.lock().unwrap_or_else(|e| e.into_inner());
-                                    if let Some(session) = sessions.get_mut(&sid_clone) {
-                                        session.child.try_wait()
-                                            .ok()
-                                            .flatten()
-                                            .map(|status| status.exit_code() as i32)
-                                            .unwrap_or(0)
-                                    } else {
-                                        0
+                                // Process ended or was shut down: reap it and report how it ended
+                                let exited =
+                                    reap_session(&child_sessions, &sid_clone, instance).await;
+                                if let Some(exit) = exited {
+                                    release_session(&sid_clone, instance);
+                                    app_handle.state::<EventStreams>().stop(&sid_clone);
+                                    emit_event(
+                                        &app_handle,
+                                        &event_name,
+                                        &task_scrollback,
+                                        exit.event(),
+                                    );
+                                    if exit.is_crash() {
+                                        handle_crash(&app_handle, &sid_clone, &exit, carry).await;
                                     }
-                                };
-
-                                let _ = app_handle.emit(&event_name, PtyEvent::Exit(exit_code));
+                                }
                                 break;
                             }
                         }
                     }
                     _ = cancel_rx.recv() => {
                         let remaining = parser.flush();
-                        let event_name = format!("pty-output-{}", sid_clone);
                         for parsed in remaining {
-                            let _ = app_handle.emit(
+                            emit_parsed(
+                                &app_handle,
                                 &event_name,
-                                PtyEvent::Parsed(parsed),
+                                &task_scrollback,
+                                &task_permissions,
+                                &task_structured,
+                                parsed,
                             );
                         }
                         break;
@@ -193,19 +512,7 @@ impl PtyManager {
             }
         });
 
-        let session = PtySession {
-            writer,
-            master: pair.master,
-            child,
-            _cancel_tx: cancel_tx,
-        };
-
-        self.sessions
-            .lock()
-            .unwrap_or_else(|e| e.into_inner())
-            .insert(session_id.to_string(), session);
-
-        Ok(())
+        Ok(replaced)
     }
 
     pub fn write_to_session(&self, session_id: &str, input: &str) -> Result<(), AppError> {
@@ -230,39 +537,418 @@ impl PtyManager {
         Ok(())
     }
 
+    /// Write bytes to the session verbatim, without the trailing newline `write_to_session` adds.
+    pub fn write_bytes(&self, session_id: &str, bytes: &[u8]) -> Result<(), AppError> {
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let session = sessions
+            .get_mut(session_id)
+            .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+
+        session
+            .writer
+            .write_all(bytes)
+            .map_err(|e| AppError::PtyError(e.to_string()))?;
+        session
+            .writer
+            .flush()
+            .map_err(|e| AppError::PtyError(e.to_string()))?;
+
+        Ok(())
+    }
+
+    pub fn send_keys(&self, session_id: &str, keys: &[KeyInput]) -> Result<(), AppError> {
+        self.write_bytes(session_id, &encode_keys(keys))
+    }
+
+    /// Answer the pending permission prompt by selecting the option at `choice` (0-based).
+    pub fn respond_to_permission(&self, session_id: &str, choice: usize) -> Result<(), AppError> {
+        let keys = {
+            let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+            let session = sessions
+                .get(session_id)
+                .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+            let keys = session
+                .permissions
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .answer(choice)?;
+            keys
+        };
+        let keys: Vec<KeyInput> = keys.into_iter().map(KeyInput::Key).collect();
+        self.send_keys(session_id, &keys)
+    }
+
+    /// Send SIGINT to the foreground process group of the session's terminal
+    /// (the running tool, or the CLI itself when idle).
+    #[cfg(unix)]
+    pub fn interrupt_session(&self, session_id: &str) -> Result<(), AppError> {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+
+        // The child is spawned with setsid(), so its pid is also its process group id
+        let pgid = session
+            .master
+            .process_group_leader()
+            .or_else(|| session.child.process_id().map(|pid| pid as libc::pid_t))
+            .ok_or_else(|| AppError::PtyError("Process id unavailable".to_string()))?;
+
+        if unsafe { libc::killpg(pgid, libc::SIGINT) } != 0 {
+            return Err(AppError::Io(std::io::Error::last_os_error()));
+        }
+        Ok(())
+    }
+
+    /// Windows has no process groups to signal; deliver Ctrl-C through the console instead.
+    #[cfg(not(unix))]
+    pub fn interrupt_session(&self, session_id: &str) -> Result<(), AppError> {
+        self.write_bytes(session_id, b"\x03")
+    }
+
+    /// Replay buffered output for a session, starting at `since_seq` when given.
+    pub fn get_output(
+        &self,
+        session_id: &str,
+        since_seq: Option<u64>,
+    ) -> Result<SessionOutput, AppError> {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+
+        let output = lock_scrollback(&session.scrollback).snapshot(since_seq);
+        Ok(output)
+    }
+
+    /// Mark a session as having a live `events.jsonl` stream, so tool executions and
+    /// file changes are no longer guessed from terminal output.
+    pub fn set_structured_events(&self, session_id: &str) -> Result<(), AppError> {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+
+        session.structured_events.store(true, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Toggle streaming of raw terminal output (`PtyEvent::Output`) for a session.
+    pub fn set_raw_output(&self, session_id: &str, enabled: bool) -> Result<(), AppError> {
+        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let session = sessions
+            .get(session_id)
+            .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+
+        session.raw_output.store(enabled, Ordering::Relaxed);
+        Ok(())
+    }
+
     pub fn resize_pty(
         &self,
         session_id: &str,
         rows: u16,
         cols: u16,
     ) -> Result<(), AppError> {
-        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
         let session = sessions
-            .get(session_id)
+            .get_mut(session_id)
             .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
 
+        let size = PtySize {
+            rows,
+            cols,
+            pixel_width: 0,
+            pixel_height: 0,
+        };
         session
             .master
-            .resize(PtySize {
-                rows,
-                cols,
-                pixel_width: 0,
-                pixel_height: 0,
-            })
+            .resize(size)
             .map_err(|e| AppError::PtyError(e.to_string()))?;
+        session.size = size;
 
         Ok(())
     }
 
-    pub fn kill_session(&self, session_id: &str) -> Result<(), AppError> {
-        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
-        let mut session = sessions
-            .remove(session_id)
-            .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+    /// Stop a session in stages: ask the CLI to `/exit` so it can flush its session
+    /// state, then SIGTERM, then kill. Runs in the background; the outcome is reported
+    /// by the `SessionExited` event once the process is gone.
+    pub fn shutdown_session(
+        &self,
+        session_id: &str,
+        timeouts: &ShutdownTimeouts,
+        app_handle: AppHandle,
+    ) -> Result<(), AppError> {
+        let instance = {
+            let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+            let session = sessions
+                .get_mut(session_id)
+                .ok_or_else(|| AppError::SessionNotFound(session_id.to_string()))?;
+            session.shutdown_requested = true;
+            session.instance
+        };
 
-        // Attempt graceful kill
-        let _ = session.child.kill();
+        let _ = self.write_to_session(session_id, "/exit");
 
+        let sessions = self.sessions.clone();
+        let sid = session_id.to_string();
+        let timeouts = timeouts.clone();
+        std::thread::spawn(move || {
+            // Otherwise the reader reaped the process and released it; by now the session
+            // may run a restored process whose overrides and account must stay
+            if staged_shutdown(&sessions, &sid, instance, &timeouts, &app_handle) {
+                release_session(&sid, instance);
+            }
+        });
         Ok(())
     }
 }
+
+/// Returns true when the session had to be dropped here rather than by its reader,
+/// which then never reaps it.
+fn staged_shutdown(
+    sessions: &SessionMap,
+    session_id: &str,
+    instance: u64,
+    timeouts: &ShutdownTimeouts,
+    app_handle: &AppHandle,
+) -> bool {
+    if wait_for_exit(sessions, session_id, instance, timeouts.exit_timeout()) {
+        return false;
+    }
+
+    #[cfg(unix)]
+    with_instance(sessions, session_id, instance, |session| {
+        if let Some(pid) = session.child.process_id() {
+            unsafe {
+                libc::kill(pid as libc::pid_t, libc::SIGTERM);
+            }
+        }
+    });
+    if wait_for_exit(sessions, session_id, instance, timeouts.term_timeout()) {
+        return false;
+    }
+
+    with_instance(sessions, session_id, instance, |session| {
+        let _ = session.child.kill();
+    });
+    if wait_for_exit(sessions, session_id, instance, REAP_TIMEOUT) {
+        return false;
+    }
+
+    // The process is gone but something still holds the terminal open, so the reader
+    // never sees EOF; drop the session here, which also stops its reader task.
+    let Some(mut session) = take_instance(sessions, session_id, instance) else {
+        return false;
+    };
+    let status = session.child.try_wait().ok().flatten();
+    let exit = ExitInfo::new(status.as_ref(), session.started_at, true);
+    emit_event(
+        app_handle,
+        &format!("pty-output-{}", session_id),
+        &session.scrollback,
+        exit.event(),
+    );
+    true
+}
+
+/// Run `f` on the session if it is still the process identified by `instance`.
+fn with_instance(
+    sessions: &SessionMap,
+    session_id: &str,
+    instance: u64,
+    f: impl FnOnce(&mut PtySession),
+) {
+    let mut sessions = sessions.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(session) = sessions.get_mut(session_id).filter(|s| s.instance == instance) {
+        f(session);
+    }
+}
+
+fn take_instance(sessions: &SessionMap, session_id: &str, instance: u64) -> Option<PtySession> {
+    let mut sessions = sessions.lock().unwrap_or_else(|e| e.into_inner());
+    if sessions.get(session_id)?.instance != instance {
+        return None;
+    }
+    sessions.remove(session_id)
+}
+
+/// Poll until the process has exited (or was already reaped), up to `timeout`.
+fn wait_for_exit(
+    sessions: &SessionMap,
+    session_id: &str,
+    instance: u64,
+    timeout: Duration,
+) -> bool {
+    let deadline = Instant::now() + timeout;
+    loop {
+        let exited = {
+            let mut sessions = sessions.lock().unwrap_or_else(|e| e.into_inner());
+            match sessions.get_mut(session_id).filter(|s| s.instance == instance) {
+                Some(session) => matches!(session.child.try_wait(), Ok(Some(_))),
+                None => true,
+            }
+        };
+        if exited {
+            return true;
+        }
+        if Instant::now() >= deadline {
+            return false;
+        }
+        std::thread::sleep(EXIT_POLL_INTERVAL);
+    }
+}
+
+/// Give back what a session held while its CLI ran: the resolved MCP overrides and
+/// its account
+fn release_session(session_id: &str, instance: u64) {
+    mcp::remove_session_overrides(session_id, instance);
+    auth::release_account(session_id);
+}
+
+/// Remove a session whose terminal has closed and report how its process ended.
+/// Returns `None` when the session was already removed or replaced by a restart.
+async fn reap_session(sessions: &SessionMap, session_id: &str, instance: u64) -> Option<ExitInfo> {
+    let deadline = Instant::now() + REAP_TIMEOUT;
+    while Instant::now() < deadline {
+        {
+            let mut map = sessions.lock().unwrap_or_else(|e| e.into_inner());
+            let session = map.get_mut(session_id).filter(|s| s.instance == instance)?;
+            if let Ok(Some(status)) = session.child.try_wait() {
+                let session = map.remove(session_id)?;
+                return Some(ExitInfo::new(
+                    Some(&status),
+                    session.started_at,
+                    session.shutdown_requested,
+                ));
+            }
+        }
+        tokio::time::sleep(EXIT_POLL_INTERVAL).await;
+    }
+
+    // Terminal closed but the process lingers: make sure it is gone
+    let mut session = take_instance(sessions, session_id, instance)?;
+    let _ = session.child.kill();
+    let status = session.child.try_wait().ok().flatten();
+    Some(ExitInfo::new(
+        status.as_ref(),
+        session.started_at,
+        session.shutdown_requested,
+    ))
+}
+
+pub(super) fn lock_scrollback(scrollback: &Mutex<Scrollback>) -> std::sync::MutexGuard<'_, Scrollback> {
+    scrollback.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Record `event` in the session scrollback and emit it with its sequence number.
+pub(super) fn emit_event(
+    app_handle: &AppHandle,
+    event_name: &str,
+    scrollback: &Mutex<Scrollback>,
+    event: PtyEvent,
+) {
+    let sequenced = lock_scrollback(scrollback).record(event);
+    let _ = app_handle.emit(event_name, sequenced);
+}
+
+/// Emit a parsed event, dropping redraws of a permission prompt already surfaced and,
+/// once the session's event log is followed, the parser's tool and file guesses.
+fn emit_parsed(
+    app_handle: &AppHandle,
+    event_name: &str,
+    scrollback: &Mutex<Scrollback>,
+    permissions: &Mutex<PermissionTracker>,
+    structured_events: &AtomicBool,
+    parsed: ParsedOutput,
+) {
+    if structured_events.load(Ordering::Relaxed)
+        && matches!(
+            parsed,
+            ParsedOutput::ToolExecution { .. } | ParsedOutput::FileChange { .. }
+        )
+    {
+        return;
+    }
+    if matches!(parsed, ParsedOutput::PermissionRequest { .. })
+        && !permissions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .offer(&parsed)
+    {
+        return;
+    }
+    emit_event(app_handle, event_name, scrollback, PtyEvent::Parsed(parsed));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spawn_args_new_session() {
+        let options = SpawnOptions {
+            working_dir: "/work/app".to_string(),
+            model: Some("gpt-5".to_string()),
+            mode: AgentMode::Suggest,
+            resume_session_id: None,
+            tool_policy: ToolPolicy {
+                deny: vec!["shell(rm *)".to_string()],
+                ..ToolPolicy::default()
+            },
+            profile: SpawnProfile::default(),
+            account: None,
+        };
+        assert_eq!(
+            spawn_args(&options),
+            vec!["--add-dir", "/work/app", "--model", "gpt-5", "--deny-tool", "shell(rm *)"]
+        );
+    }
+
+    #[test]
+    fn test_spawn_args_resume() {
+        let options = SpawnOptions {
+            working_dir: "/work/app".to_string(),
+            model: None,
+            mode: AgentMode::Autopilot,
+            resume_session_id: Some("cli-1".to_string()),
+            tool_policy: ToolPolicy::default(),
+            profile: SpawnProfile::default(),
+            account: None,
+        };
+        assert_eq!(spawn_args(&options), vec!["--resume", "cli-1", "--allow-all-tools"]);
+    }
+
+    #[test]
+    fn test_mcp_config_args() {
+        assert_eq!(
+            mcp_config_args(Some(Path::new("/home/me/.copilot-desktop/mcp-sessions/s.json"))),
+            vec![
+                "--additional-mcp-config",
+                "@/home/me/.copilot-desktop/mcp-sessions/s.json"
+            ]
+        );
+        assert!(mcp_config_args(None).is_empty());
+    }
+
+    #[test]
+    fn test_spawn_args_profile_dirs_and_extra_flags() {
+        let options = SpawnOptions {
+            working_dir: "/work/app".to_string(),
+            model: None,
+            mode: AgentMode::Suggest,
+            resume_session_id: None,
+            tool_policy: ToolPolicy::default(),
+            profile: SpawnProfile {
+                add_dirs: vec!["/work/libs".to_string()],
+                extra_args: vec!["--log-level".to_string(), "debug".to_string()],
+                ..SpawnProfile::default()
+            },
+            account: None,
+        };
+        assert_eq!(
+            spawn_args(&options),
+            vec!["--add-dir", "/work/app", "--add-dir", "/work/libs", "--log-level", "debug"]
+        );
+    }
+}
@@ -1,4 +1,11 @@
+pub mod cli;
+mod decoder;
+mod keys;
 mod manager;
 pub mod parser;
+mod permission;
+mod profile;
+mod scrollback;
+mod supervisor;
 
-pub use manager::PtyManager;
+pub use manager::{spawn_args, PtyManager, SpawnOptions};
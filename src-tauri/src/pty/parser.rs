@@ -1,5 +1,15 @@
 use crate::types::ParsedOutput;
 
+/// Number of preceding lines kept to describe what a permission prompt is asking about
+const PERMISSION_CONTEXT_LINES: usize = 4;
+
+/// Permission prompt whose menu options are still being read
+struct PendingPermission {
+    tool: String,
+    command: String,
+    options: Vec<String>,
+}
+
 /// Streaming ANSI output parser that detects structured content
 /// from Copilot CLI output (code blocks, tool executions, file changes, etc.)
 pub struct AnsiParser {
@@ -13,6 +23,12 @@ pub struct AnsiParser {
     code_block_buffer: String,
     /// Reserved for handling incomplete ANSI escape sequences in future
     _incomplete_escape: bool,
+    /// Recent non-empty lines, used as context when a permission question appears
+    recent_lines: Vec<String>,
+    /// Permission prompt currently being assembled
+    permission: Option<PendingPermission>,
+    /// Permission request finished by the line being processed, emitted before that line
+    completed_permission: Option<ParsedOutput>,
 }
 
 impl AnsiParser {
@@ -23,6 +39,9 @@ impl AnsiParser {
             code_block_language: None,
             code_block_buffer: String::new(),
             _incomplete_escape: false,
+            recent_lines: Vec::new(),
+            permission: None,
+            completed_permission: None,
         }
     }
 
@@ -35,9 +54,7 @@ impl AnsiParser {
         for ch in stripped.chars() {
             if ch == '\n' {
                 let line = std::mem::take(&mut self.line_buffer);
-                if let Some(parsed) = self.process_line(&line) {
-                    results.push(parsed);
-                }
+                self.process_line_into(&line, &mut results);
             } else if ch != '\r' {
                 self.line_buffer.push(ch);
             }
@@ -52,9 +69,12 @@ impl AnsiParser {
 
         if !self.line_buffer.is_empty() {
             let line = std::mem::take(&mut self.line_buffer);
-            if let Some(parsed) = self.process_line(&line) {
-                results.push(parsed);
-            }
+            self.process_line_into(&line, &mut results);
+        }
+
+        // A prompt still waiting for input is complete with the options seen so far
+        if let Some(request) = self.finish_permission() {
+            results.push(request);
         }
 
         // Close any open code block
@@ -68,6 +88,28 @@ impl AnsiParser {
         results
     }
 
+    fn finish_permission(&mut self) -> Option<ParsedOutput> {
+        let pending = self.permission.take()?;
+        if pending.options.is_empty() {
+            return None;
+        }
+        Some(ParsedOutput::PermissionRequest {
+            tool: pending.tool,
+            command: pending.command,
+            options: pending.options,
+        })
+    }
+
+    fn process_line_into(&mut self, line: &str, results: &mut Vec<ParsedOutput>) {
+        let parsed = self.process_line(line);
+        if let Some(request) = self.completed_permission.take() {
+            results.push(request);
+        }
+        if let Some(parsed) = parsed {
+            results.push(parsed);
+        }
+    }
+
     fn process_line(&mut self, line: &str) -> Option<ParsedOutput> {
         let trimmed = line.trim();
 
@@ -102,6 +144,42 @@ impl AnsiParser {
             return None;
         }
 
+        // Permission prompts are drawn inside a box; look at the content without borders
+        let content = strip_box_border(trimmed);
+        if self.permission.is_some() {
+            if let Some(label) = parse_permission_option(content) {
+                let is_last = is_final_option(&label);
+                if let Some(pending) = self.permission.as_mut() {
+                    pending.options.push(label);
+                }
+                return if is_last { self.finish_permission() } else { None };
+            }
+            if content.is_empty() && !trimmed.starts_with('╰') {
+                return None;
+            }
+            // Anything else closes the menu
+            self.completed_permission = self.finish_permission();
+        }
+        if trimmed.starts_with('╭') {
+            self.recent_lines.clear();
+        }
+        if is_permission_question(content) {
+            let (tool, command) = describe_permission(content, &self.recent_lines);
+            self.permission = Some(PendingPermission {
+                tool,
+                command,
+                options: Vec::new(),
+            });
+            self.recent_lines.clear();
+            return None;
+        }
+        if !content.is_empty() {
+            self.recent_lines.push(content.to_string());
+            if self.recent_lines.len() > PERMISSION_CONTEXT_LINES {
+                self.recent_lines.remove(0);
+            }
+        }
+
         // Skip empty lines
         if trimmed.is_empty() {
             return None;
@@ -132,6 +210,81 @@ impl AnsiParser {
     }
 }
 
+/// Remove box-drawing borders around a line of a Copilot CLI dialog.
+fn strip_box_border(line: &str) -> &str {
+    line.trim_matches(|c: char| matches!(c, '│' | '╭' | '╮' | '╰' | '╯' | '─') || c.is_whitespace())
+}
+
+/// Questions like "Do you want to run this command?" that precede a numbered menu.
+fn is_permission_question(line: &str) -> bool {
+    let lower = line.to_lowercase();
+    lower.ends_with('?')
+        && (lower.starts_with("do you want to")
+            || lower.starts_with("allow ")
+            || lower.starts_with("would you like to allow"))
+}
+
+/// Menu entries like "1. Yes", "❯ 2. No (Esc)" or "> 1. Yes"; returns the label.
+fn parse_permission_option(line: &str) -> Option<String> {
+    let line = line.trim_start_matches(['❯', '>', '›']).trim_start();
+    let (number, label) = line.split_once(". ")?;
+    if number.is_empty() || !number.chars().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let label = label.trim();
+    (!label.is_empty()).then(|| label.to_string())
+}
+
+/// The deny/cancel entry is always the last one in the CLI's menus.
+fn is_final_option(label: &str) -> bool {
+    let lower = label.to_lowercase();
+    lower.starts_with("no") || lower.contains("(esc)")
+}
+
+/// Work out which tool the prompt is about and the command or path it targets,
+/// from the question itself and the lines shown above it.
+fn describe_permission(question: &str, context: &[String]) -> (String, String) {
+    let mut tool = None;
+    let mut command = None;
+
+    for line in context.iter().rev() {
+        if let Some(cmd) = line.strip_prefix("$ ") {
+            tool.get_or_insert("shell");
+            command.get_or_insert_with(|| cmd.trim().to_string());
+            continue;
+        }
+        let lower = line.to_lowercase();
+        if lower.contains("shell") || lower.contains("command") {
+            tool.get_or_insert("shell");
+        } else if lower.contains("file") && ["edit", "write", "create"].iter().any(|w| lower.contains(w)) {
+            tool.get_or_insert("write");
+        } else if lower.contains("mcp") {
+            tool.get_or_insert("mcp");
+        } else if lower.contains("url") || lower.contains("fetch") {
+            tool.get_or_insert("url");
+        } else {
+            command.get_or_insert_with(|| line.clone());
+        }
+    }
+
+    // "Allow Copilot to run `git push`?" names the command inline
+    let inline = question
+        .split('`')
+        .nth(1)
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string());
+    let command = inline.or(command).unwrap_or_else(|| question.to_string());
+    let tool = tool.unwrap_or_else(|| {
+        if question.to_lowercase().contains("run") {
+            "shell"
+        } else {
+            "unknown"
+        }
+    });
+
+    (tool.to_string(), command)
+}
+
 /// Strip ANSI escape sequences from a string, handling incomplete sequences gracefully.
 fn strip_ansi(input: &str) -> String {
     strip_ansi_escapes::strip_str(input).to_string()
@@ -334,4 +487,58 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert!(matches!(&results[0], ParsedOutput::Text(s) if s == "Green text"));
     }
+
+    #[test]
+    fn test_shell_permission_prompt() {
+        let mut parser = AnsiParser::new();
+        let results = parser.feed(concat!(
+            "╭──────────────────────────────╮\n",
+            "│ Run shell command            │\n",
+            "│ $ git push origin main       │\n",
+            "│ Do you want to run this command? │\n",
+            "│ ❯ 1. Yes                     │\n",
+            "│   2. Yes, and approve `git` for the rest of the session │\n",
+            "│   3. No, and tell Copilot what to do differently (Esc) │\n",
+        ));
+        let request = results.last().unwrap();
+        match request {
+            ParsedOutput::PermissionRequest { tool, command, options } => {
+                assert_eq!(tool, "shell");
+                assert_eq!(command, "git push origin main");
+                assert_eq!(options.len(), 3);
+                assert_eq!(options[0], "Yes");
+            }
+            other => panic!("Expected PermissionRequest, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_write_permission_prompt_closed_by_next_line() {
+        let mut parser = AnsiParser::new();
+        let results = parser.feed(concat!(
+            "Edit file\n",
+            "src/main.rs\n",
+            "Do you want to make this edit?\n",
+            "1. Yes\n",
+            "2. Always allow edits\n",
+            "Thinking: next step\n",
+        ));
+        let request_index = results
+            .iter()
+            .position(|r| matches!(r, ParsedOutput::PermissionRequest { .. }))
+            .expect("permission request");
+        assert!(matches!(
+            &results[request_index],
+            ParsedOutput::PermissionRequest { tool, command, options }
+                if tool == "write" && command == "src/main.rs" && options.len() == 2
+        ));
+        assert!(matches!(&results[request_index + 1], ParsedOutput::Thinking(_)));
+    }
+
+    #[test]
+    fn test_numbered_list_without_question_is_text() {
+        let mut parser = AnsiParser::new();
+        let results = parser.feed("1. First step\n");
+        assert!(matches!(&results[0], ParsedOutput::Text(s) if s == "1. First step"));
+    }
 }
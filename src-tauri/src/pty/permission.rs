@@ -0,0 +1,99 @@
+use std::time::{Duration, Instant};
+
+use crate::types::{AppError, NamedKey, ParsedOutput};
+
+/// How long a just-answered prompt is ignored if the CLI redraws it
+const ANSWERED_GRACE: Duration = Duration::from_secs(5);
+
+/// Tracks the permission prompt a session is waiting on.
+///
+/// The CLI redraws its menus (e.g. while the selection moves), so the parser can
+/// report the same prompt several times; only the first sighting is surfaced.
+pub struct PermissionTracker {
+    pending: Option<ParsedOutput>,
+    answered: Option<(ParsedOutput, Instant)>,
+}
+
+impl PermissionTracker {
+    pub fn new() -> Self {
+        Self {
+            pending: None,
+            answered: None,
+        }
+    }
+
+    /// Returns false when `request` is a redraw of a prompt already surfaced.
+    pub fn offer(&mut self, request: &ParsedOutput) -> bool {
+        if self.pending.as_ref() == Some(request) {
+            return false;
+        }
+        if let Some((answered, at)) = &self.answered {
+            if answered == request && at.elapsed() < ANSWERED_GRACE {
+                return false;
+            }
+        }
+        self.pending = Some(request.clone());
+        true
+    }
+
+    /// Resolve the pending prompt with the option at `choice` and return the keys
+    /// that select it, assuming the menu opens with its first option highlighted.
+    pub fn answer(&mut self, choice: usize) -> Result<Vec<NamedKey>, AppError> {
+        let option_count = match &self.pending {
+            Some(ParsedOutput::PermissionRequest { options, .. }) => options.len(),
+            _ => return Err(AppError::Other("No pending permission request".to_string())),
+        };
+        if choice >= option_count {
+            return Err(AppError::Other(format!(
+                "Invalid permission choice {} (prompt has {} options)",
+                choice, option_count
+            )));
+        }
+
+        if let Some(request) = self.pending.take() {
+            self.answered = Some((request, Instant::now()));
+        }
+        let mut keys = vec![NamedKey::Down; choice];
+        keys.push(NamedKey::Enter);
+        Ok(keys)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(command: &str) -> ParsedOutput {
+        ParsedOutput::PermissionRequest {
+            tool: "shell".to_string(),
+            command: command.to_string(),
+            options: vec!["Yes".to_string(), "No (Esc)".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_redraws_are_suppressed() {
+        let mut tracker = PermissionTracker::new();
+        assert!(tracker.offer(&request("ls")));
+        assert!(!tracker.offer(&request("ls")));
+        tracker.answer(0).unwrap();
+        assert!(!tracker.offer(&request("ls")));
+        assert!(tracker.offer(&request("rm -rf build")));
+    }
+
+    #[test]
+    fn test_answer_drives_menu() {
+        let mut tracker = PermissionTracker::new();
+        tracker.offer(&request("ls"));
+        assert_eq!(tracker.answer(1).unwrap(), vec![NamedKey::Down, NamedKey::Enter]);
+        assert!(tracker.answer(0).is_err());
+    }
+
+    #[test]
+    fn test_answer_rejects_out_of_range_choice() {
+        let mut tracker = PermissionTracker::new();
+        tracker.offer(&request("ls"));
+        assert!(tracker.answer(2).is_err());
+        assert_eq!(tracker.answer(0).unwrap(), vec![NamedKey::Enter]);
+    }
+}
@@ -0,0 +1,121 @@
+use serde::{Deserialize, Serialize};
+
+use crate::config::ToolPolicy;
+use crate::types::AgentMode;
+
+/// Tool permissions handed to the Copilot CLI at spawn time.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct PermissionProfile {
+    /// `--allow-all-tools`: never prompt
+    pub allow_all_tools: bool,
+    /// `--allow-tool` specs, e.g. `write` or `shell(git status)`
+    pub allowed_tools: Vec<String>,
+    /// `--deny-tool` specs; these win over any allow rule
+    pub denied_tools: Vec<String>,
+    /// `--allow-all-paths`: don't restrict tools to the project directories
+    pub allow_all_paths: bool,
+}
+
+impl PermissionProfile {
+    /// Baseline profile for an agent mode:
+    /// - Suggest: every tool call is confirmed by the user
+    /// - AutoEdit: file edits are applied without asking, shell commands still prompt
+    /// - Autopilot: all tools run without confirmation
+    pub fn for_mode(mode: &AgentMode) -> Self {
+        match mode {
+            AgentMode::Suggest => Self::default(),
+            AgentMode::AutoEdit => Self {
+                allowed_tools: vec!["write".to_string()],
+                ..Self::default()
+            },
+            AgentMode::Autopilot => Self {
+                allow_all_tools: true,
+                ..Self::default()
+            },
+        }
+    }
+
+    /// Add the user's allow/deny rules to the mode baseline.
+    pub fn with_policy(mut self, policy: &ToolPolicy) -> Self {
+        for rule in &policy.allow {
+            if !self.allowed_tools.contains(rule) {
+                self.allowed_tools.push(rule.clone());
+            }
+        }
+        for rule in &policy.deny {
+            if !self.denied_tools.contains(rule) {
+                self.denied_tools.push(rule.clone());
+            }
+        }
+        self.allow_all_paths |= policy.allow_all_paths;
+        self
+    }
+
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.allow_all_tools {
+            args.push("--allow-all-tools".to_string());
+        }
+        if self.allow_all_paths {
+            args.push("--allow-all-paths".to_string());
+        }
+        for tool in &self.allowed_tools {
+            args.push("--allow-tool".to_string());
+            args.push(tool.clone());
+        }
+        for tool in &self.denied_tools {
+            args.push("--deny-tool".to_string());
+            args.push(tool.clone());
+        }
+        args
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest_has_no_permission_flags() {
+        assert!(PermissionProfile::for_mode(&AgentMode::Suggest).to_args().is_empty());
+    }
+
+    #[test]
+    fn test_autoedit_allows_writes_only() {
+        assert_eq!(
+            PermissionProfile::for_mode(&AgentMode::AutoEdit).to_args(),
+            vec!["--allow-tool", "write"]
+        );
+    }
+
+    #[test]
+    fn test_autopilot_allows_all_tools() {
+        assert_eq!(
+            PermissionProfile::for_mode(&AgentMode::Autopilot).to_args(),
+            vec!["--allow-all-tools"]
+        );
+    }
+
+    #[test]
+    fn test_policy_rules_extend_mode_profile() {
+        let policy = ToolPolicy {
+            allow: vec!["shell(git status)".into(), "write".into()],
+            deny: vec!["shell(rm *)".into()],
+            allow_all_paths: false,
+        };
+        let args = PermissionProfile::for_mode(&AgentMode::AutoEdit)
+            .with_policy(&policy)
+            .to_args();
+        assert_eq!(
+            args,
+            vec![
+                "--allow-tool",
+                "write",
+                "--allow-tool",
+                "shell(git status)",
+                "--deny-tool",
+                "shell(rm *)"
+            ]
+        );
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::VecDeque;
+
+use crate::types::{PtyEvent, SequencedPtyEvent, SessionOutput};
+
+const MAX_EVENTS: usize = 10_000;
+const MAX_RAW_BYTES: usize = 2 * 1024 * 1024; // 2MB
+
+/// Bounded per-session history of PTY output.
+///
+/// Every event emitted for a session gets a monotonically increasing sequence
+/// number so a reloaded webview can fetch what it missed via `since_seq` and
+/// then drop live events it has already seen.
+pub struct Scrollback {
+    next_seq: u64,
+    events: VecDeque<SequencedPtyEvent>,
+    raw: VecDeque<u8>,
+    max_events: usize,
+    max_raw_bytes: usize,
+}
+
+impl Scrollback {
+    pub fn new() -> Self {
+        Self::with_limits(MAX_EVENTS, MAX_RAW_BYTES)
+    }
+
+    pub fn with_limits(max_events: usize, max_raw_bytes: usize) -> Self {
+        Self {
+            next_seq: 0,
+            events: VecDeque::new(),
+            raw: VecDeque::new(),
+            max_events,
+            max_raw_bytes,
+        }
+    }
+
+    /// Assign the next sequence number to `event` and keep it for replay.
+    /// Raw `Output` events are numbered but not retained; the raw byte buffer replays them.
+    pub fn record(&mut self, event: PtyEvent) -> SequencedPtyEvent {
+        let sequenced = SequencedPtyEvent {
+            seq: self.next_seq,
+            event,
+        };
+        self.next_seq += 1;
+
+        if matches!(sequenced.event, PtyEvent::Output(_)) {
+            return sequenced;
+        }
+        self.events.push_back(sequenced.clone());
+        while self.events.len() > self.max_events {
+            self.events.pop_front();
+        }
+        sequenced
+    }
+
+    /// Append undecoded PTY bytes, discarding the oldest ones past the limit.
+    pub fn append_raw(&mut self, bytes: &[u8]) {
+        self.raw.extend(bytes);
+        if self.raw.len() > self.max_raw_bytes {
+            let excess = self.raw.len() - self.max_raw_bytes;
+            self.raw.drain(..excess);
+            // Never start the buffer in the middle of a UTF-8 sequence
+            while self.raw.front().is_some_and(|b| b & 0xC0 == 0x80) {
+                self.raw.pop_front();
+            }
+        }
+    }
+
+    /// The last `n` non-blank lines of terminal output, with ANSI escapes removed.
+    /// Carriage-return redraws (spinners, progress) keep only their final state.
+    pub fn tail_lines(&self, n: usize) -> Vec<String> {
+        let (front, back) = self.raw.as_slices();
+        let text = String::from_utf8_lossy(&[front, back].concat()).into_owned();
+        let mut lines: Vec<String> = text
+            .lines()
+            .filter_map(|line| {
+                line.rsplit('\r')
+                    .map(|part| strip_ansi_escapes::strip_str(part).trim_end().to_string())
+                    .find(|part| !part.trim().is_empty())
+            })
+            .collect();
+        lines.drain(..lines.len().saturating_sub(n));
+        lines
+    }
+
+    /// Events with `seq >= since_seq` (all retained events when `None`) and the raw buffer.
+    pub fn snapshot(&self, since_seq: Option<u64>) -> SessionOutput {
+        let since = since_seq.unwrap_or(0);
+        let first_retained = self.events.front().map(|e| e.seq).unwrap_or(self.next_seq);
+        let events = self
+            .events
+            .iter()
+            .filter(|e| e.seq >= since)
+            .cloned()
+            .collect();
+        let (front, back) = self.raw.as_slices();
+        let raw = String::from_utf8_lossy(&[front, back].concat()).into_owned();
+
+        SessionOutput {
+            events,
+            raw,
+            next_seq: self.next_seq,
+            truncated: since < first_retained,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ParsedOutput;
+
+    fn text(s: &str) -> PtyEvent {
+        PtyEvent::Parsed(ParsedOutput::Text(s.to_string()))
+    }
+
+    #[test]
+    fn test_sequence_numbers_increase() {
+        let mut scrollback = Scrollback::new();
+        assert_eq!(scrollback.record(text("a")).seq, 0);
+        assert_eq!(scrollback.record(text("b")).seq, 1);
+        assert_eq!(scrollback.snapshot(None).next_seq, 2);
+    }
+
+    #[test]
+    fn test_snapshot_since_seq() {
+        let mut scrollback = Scrollback::new();
+        for s in ["a", "b", "c"] {
+            scrollback.record(text(s));
+        }
+        let output = scrollback.snapshot(Some(1));
+        assert_eq!(output.events.len(), 2);
+        assert_eq!(output.events[0].seq, 1);
+        assert!(!output.truncated);
+    }
+
+    #[test]
+    fn test_raw_output_events_not_retained() {
+        let mut scrollback = Scrollback::new();
+        scrollback.record(PtyEvent::Output("\x1b[2J".to_string()));
+        scrollback.record(text("a"));
+        let output = scrollback.snapshot(None);
+        assert_eq!(output.events.len(), 1);
+        assert_eq!(output.events[0].seq, 1);
+        assert_eq!(output.next_seq, 2);
+    }
+
+    #[test]
+    fn test_event_limit_marks_truncated() {
+        let mut scrollback = Scrollback::with_limits(2, 16);
+        for s in ["a", "b", "c"] {
+            scrollback.record(text(s));
+        }
+        let output = scrollback.snapshot(Some(0));
+        assert_eq!(output.events.len(), 2);
+        assert_eq!(output.events[0].seq, 1);
+        assert!(output.truncated);
+        assert!(!scrollback.snapshot(Some(1)).truncated);
+    }
+
+    #[test]
+    fn test_raw_limit_keeps_utf8_boundary() {
+        let mut scrollback = Scrollback::with_limits(10, 4);
+        scrollback.append_raw("ab".as_bytes());
+        scrollback.append_raw("é!".as_bytes()); // 'é' is two bytes
+        assert_eq!(scrollback.snapshot(None).raw, "bé!");
+        scrollback.append_raw(b"xy");
+        assert_eq!(scrollback.snapshot(None).raw, "!xy");
+    }
+
+    #[test]
+    fn test_tail_lines_strips_ansi_and_redraws() {
+        let mut scrollback = Scrollback::new();
+        scrollback.append_raw(b"one\r\n\x1b[31mtwo\x1b[0m\n\nloading 1%\rloading 100%\nthree");
+        assert_eq!(scrollback.tail_lines(3), vec!["two", "loading 100%", "three"]);
+        assert_eq!(scrollback.tail_lines(10).len(), 4);
+    }
+
+    #[test]
+    fn test_sequenced_event_serializes_flat() {
+        let mut scrollback = Scrollback::new();
+        let exited = PtyEvent::SessionExited {
+            code: Some(0),
+            signal: None,
+            duration: 1500,
+        };
+        let json = serde_json::to_value(scrollback.record(exited)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "seq": 0,
+                "type": "SessionExited",
+                "data": { "code": 0, "signal": null, "duration": 1500 }
+            })
+        );
+    }
+}
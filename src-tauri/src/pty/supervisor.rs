@@ -0,0 +1,165 @@
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use tauri::{AppHandle, Manager};
+
+use super::manager::{emit_event, lock_scrollback, Carryover, PtyManager, SpawnOptions};
+use crate::config::ConfigManager;
+use crate::session::{EventStreams, SessionManager};
+use crate::types::{CrashReport, PtyEvent};
+
+/// Lines of terminal output kept in a crash report
+const CRASH_REPORT_LINES: usize = 50;
+
+/// How a Copilot process ended
+pub(super) struct ExitInfo {
+    pub code: Option<i32>,
+    pub signal: Option<String>,
+    pub duration: Duration,
+    /// The exit was asked for by `shutdown_session`
+    pub requested: bool,
+}
+
+impl ExitInfo {
+    pub fn new(
+        status: Option<&portable_pty::ExitStatus>,
+        started_at: Instant,
+        requested: bool,
+    ) -> Self {
+        Self {
+            code: status
+                .filter(|s| s.signal().is_none())
+                .map(|s| s.exit_code() as i32),
+            signal: status.and_then(|s| s.signal()).map(|s| s.to_string()),
+            duration: started_at.elapsed(),
+            requested,
+        }
+    }
+
+    pub fn event(&self) -> PtyEvent {
+        PtyEvent::SessionExited {
+            code: self.code,
+            signal: self.signal.clone(),
+            duration: self.duration.as_millis() as u64,
+        }
+    }
+
+    /// Anything but a clean exit the user didn't ask for counts as a crash. A `/exit`
+    /// typed into the terminal ends with code 0, so it is reported as an exit and the
+    /// session isn't restarted.
+    pub fn is_crash(&self) -> bool {
+        !self.requested && self.code != Some(0)
+    }
+}
+
+/// Record a crash on the session, report it, and restart the process with
+/// `--resume` after the backoff delay when the restart policy allows it.
+pub(super) async fn handle_crash(
+    app_handle: &AppHandle,
+    session_id: &str,
+    exit: &ExitInfo,
+    carry: Carryover,
+) {
+    let event_name = format!("pty-output-{}", session_id);
+    let config = app_handle.state::<ConfigManager>();
+    let session_mgr = app_handle.state::<SessionManager>();
+    let pty = app_handle.state::<PtyManager>();
+
+    let report = CrashReport {
+        code: exit.code,
+        signal: exit.signal.clone(),
+        duration: exit.duration.as_millis() as u64,
+        crashed_at: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs(),
+        last_lines: lock_scrollback(&carry.scrollback).tail_lines(CRASH_REPORT_LINES),
+    };
+    // A session closed while its process was dying has nothing left to restart
+    if !session_mgr.set_crash_report(session_id, Some(report.clone())) {
+        return;
+    }
+
+    let policy = config.get_config().restart;
+    let attempt = if exit.duration >= policy.stable_after() {
+        0
+    } else {
+        carry.crash_restarts
+    };
+    let delay = policy.backoff(attempt);
+    emit_event(
+        app_handle,
+        &event_name,
+        &carry.scrollback,
+        PtyEvent::SessionCrashed {
+            report,
+            restart_in: delay.map(|d| d.as_millis() as u64),
+        },
+    );
+
+    let Some(delay) = delay else {
+        return;
+    };
+    tokio::time::sleep(delay).await;
+
+    // The user may have closed or restored the session in the meantime
+    if pty.has_session(session_id) {
+        return;
+    }
+    session_mgr.link_copilot_sessions();
+    let Some(session) = session_mgr.get_session(session_id) else {
+        return;
+    };
+
+    let options = SpawnOptions::for_session(&session, &config.get_config());
+    let carry = Carryover {
+        crash_restarts: attempt + 1,
+        ..carry
+    };
+    match pty.spawn_with_output(session_id, &options, carry.clone(), app_handle.clone()) {
+        Ok(_) => {
+            session_mgr.set_crash_report(session_id, None);
+            app_handle
+                .state::<EventStreams>()
+                .start(session_id, app_handle.clone());
+        }
+        Err(e) => emit_event(
+            app_handle,
+            &event_name,
+            &carry.scrollback,
+            PtyEvent::Error(format!("Failed to restart Copilot: {}", e)),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use portable_pty::ExitStatus;
+
+    #[test]
+    fn test_exit_classification() {
+        let started = Instant::now();
+        let clean = ExitStatus::with_exit_code(0);
+        let failed = ExitStatus::with_exit_code(1);
+        let killed = ExitStatus::with_signal("Killed");
+
+        assert!(!ExitInfo::new(Some(&clean), started, false).is_crash());
+        assert!(ExitInfo::new(Some(&failed), started, false).is_crash());
+        assert!(!ExitInfo::new(Some(&failed), started, true).is_crash());
+        assert!(ExitInfo::new(None, started, false).is_crash());
+
+        let exit = ExitInfo::new(Some(&killed), started, false);
+        assert!(exit.is_crash());
+        assert_eq!(exit.code, None);
+        assert_eq!(exit.signal.as_deref(), Some("Killed"));
+    }
+
+    #[test]
+    fn test_typed_exit_is_not_a_crash() {
+        let started = Instant::now() - Duration::from_secs(600);
+        let exit = ExitInfo::new(Some(&ExitStatus::with_exit_code(0)), started, false);
+        assert!(!exit.requested);
+        assert!(!exit.is_crash());
+        assert!(matches!(exit.event(), PtyEvent::SessionExited { code: Some(0), .. }));
+    }
+}
@@ -0,0 +1,505 @@
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::SessionEvent;
+
+/// One line of a Copilot CLI `events.jsonl` file.
+#[derive(Debug, Clone, Serialize)]
+pub struct EventEnvelope {
+    pub id: String,
+    pub timestamp: String,
+    pub parent_id: Option<String>,
+    #[serde(flatten)]
+    pub event: CopilotEvent,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawEvent {
+    #[serde(default)]
+    id: String,
+    #[serde(default)]
+    timestamp: String,
+    #[serde(default)]
+    parent_id: Option<String>,
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    data: Option<serde_json::Value>,
+}
+
+/// Event kinds written by the Copilot CLI to `events.jsonl`.
+///
+/// Kinds this app doesn't know, and known kinds whose data doesn't match, are kept
+/// as `Unknown` with their raw data so a newer CLI never breaks parsing.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", content = "data")]
+pub enum CopilotEvent {
+    #[serde(rename = "session.start")]
+    SessionStart(SessionStartData),
+    #[serde(rename = "session.resume")]
+    SessionResume(SessionResumeData),
+    #[serde(rename = "session.info")]
+    SessionInfo(SessionInfoData),
+    #[serde(rename = "session.error")]
+    Error(ErrorData),
+    #[serde(rename = "session.model_change")]
+    ModelChange(ModelChangeData),
+    #[serde(rename = "session.compaction_start")]
+    CompactionStart(CompactionStartData),
+    #[serde(rename = "session.compaction_complete")]
+    CompactionComplete(CompactionCompleteData),
+    #[serde(rename = "user.message")]
+    UserMessage(UserMessageData),
+    #[serde(rename = "assistant.turn_start")]
+    TurnStart(TurnData),
+    #[serde(rename = "assistant.turn_end")]
+    TurnEnd(TurnData),
+    #[serde(rename = "assistant.message")]
+    AssistantMessage(AssistantMessageData),
+    #[serde(rename = "assistant.reasoning")]
+    Reasoning(ReasoningData),
+    #[serde(rename = "assistant.usage")]
+    Usage(UsageData),
+    #[serde(rename = "tool.execution_start")]
+    ToolExecutionStart(ToolStartData),
+    #[serde(rename = "tool.execution_complete")]
+    ToolExecutionComplete(ToolCompleteData),
+    #[serde(rename = "abort")]
+    Abort(AbortData),
+    /// Serialized untagged as `{ "type": kind, "data": data }`, the way the CLI wrote it
+    #[serde(skip_deserializing, untagged)]
+    Unknown {
+        #[serde(rename = "type")]
+        kind: String,
+        data: serde_json::Value,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionStartData {
+    pub session_id: String,
+    pub copilot_version: Option<String>,
+    pub start_time: Option<String>,
+    pub context: Option<SessionContext>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionContext {
+    pub cwd: Option<String>,
+    pub git_root: Option<String>,
+    pub repository: Option<String>,
+    pub branch: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionResumeData {
+    pub resume_time: Option<String>,
+    pub event_count: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SessionInfoData {
+    pub info_type: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ErrorData {
+    pub error_type: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ModelChangeData {
+    pub previous_model: Option<String>,
+    pub new_model: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CompactionStartData {
+    pub pre_compaction_tokens: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct CompactionCompleteData {
+    pub success: bool,
+    pub pre_compaction_tokens: Option<u64>,
+    pub post_compaction_tokens: Option<u64>,
+    pub summary_content: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UserMessageData {
+    pub content: String,
+    /// The prompt as sent to the model, after attachments and instructions were added
+    pub transformed_content: Option<String>,
+    pub attachments: Vec<serde_json::Value>,
+    pub source: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct TurnData {
+    pub turn_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AssistantMessageData {
+    pub message_id: String,
+    pub content: String,
+    pub tool_requests: Vec<ToolRequest>,
+    /// Set when the message was produced inside a sub-agent tool call
+    pub parent_tool_call_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToolRequest {
+    pub tool_call_id: String,
+    pub name: String,
+    pub arguments: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ReasoningData {
+    pub reasoning_id: String,
+    pub content: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct UsageData {
+    pub model: Option<String>,
+    pub input_tokens: Option<u64>,
+    pub output_tokens: Option<u64>,
+    pub cache_read_tokens: Option<u64>,
+    pub cache_write_tokens: Option<u64>,
+    /// Premium request multiplier charged for the call
+    pub cost: Option<f64>,
+    /// Request duration in milliseconds
+    pub duration: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToolStartData {
+    pub tool_call_id: String,
+    pub tool_name: String,
+    pub arguments: serde_json::Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToolCompleteData {
+    pub tool_call_id: String,
+    pub success: bool,
+    pub result: Option<ToolResultData>,
+    pub error: Option<ErrorData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ToolResultData {
+    pub content: String,
+    pub detailed_content: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct AbortData {
+    pub reason: Option<String>,
+}
+
+impl CopilotEvent {
+    /// The `type` the CLI wrote for this event, e.g. `tool.execution_start`
+    pub fn kind(&self) -> &str {
+        match self {
+            CopilotEvent::SessionStart(_) => "session.start",
+            CopilotEvent::SessionResume(_) => "session.resume",
+            CopilotEvent::SessionInfo(_) => "session.info",
+            CopilotEvent::Error(_) => "session.error",
+            CopilotEvent::ModelChange(_) => "session.model_change",
+            CopilotEvent::CompactionStart(_) => "session.compaction_start",
+            CopilotEvent::CompactionComplete(_) => "session.compaction_complete",
+            CopilotEvent::UserMessage(_) => "user.message",
+            CopilotEvent::TurnStart(_) => "assistant.turn_start",
+            CopilotEvent::TurnEnd(_) => "assistant.turn_end",
+            CopilotEvent::AssistantMessage(_) => "assistant.message",
+            CopilotEvent::Reasoning(_) => "assistant.reasoning",
+            CopilotEvent::Usage(_) => "assistant.usage",
+            CopilotEvent::ToolExecutionStart(_) => "tool.execution_start",
+            CopilotEvent::ToolExecutionComplete(_) => "tool.execution_complete",
+            CopilotEvent::Abort(_) => "abort",
+            CopilotEvent::Unknown { kind, .. } => kind,
+        }
+    }
+
+    /// Whether the event matches a filter entry: either an exact kind or a
+    /// namespace such as `tool` or `session`.
+    pub fn matches_kind(&self, filter: &str) -> bool {
+        let kind = self.kind();
+        kind == filter
+            || kind
+                .strip_prefix(filter)
+                .is_some_and(|rest| rest.starts_with('.'))
+    }
+}
+
+/// Tools whose `path` argument is a file the agent creates or modifies
+const FILE_EDIT_TOOLS: &[&str] = &["edit", "create", "str_replace_editor", "write"];
+
+/// Parse one line; `None` only when the line isn't a JSON event at all.
+pub fn parse_event_line(line: &str) -> Option<EventEnvelope> {
+    let raw: RawEvent = serde_json::from_str(line).ok()?;
+    let data = raw.data.unwrap_or_else(|| serde_json::json!({}));
+    let event = serde_json::from_value(serde_json::json!({ "type": raw.kind, "data": data }))
+        .unwrap_or(CopilotEvent::Unknown {
+            kind: raw.kind,
+            data,
+        });
+    Some(EventEnvelope {
+        id: raw.id,
+        timestamp: raw.timestamp,
+        parent_id: raw.parent_id,
+        event,
+    })
+}
+
+/// Every event of an `events.jsonl` file, skipping lines that aren't JSON.
+pub fn read_events(path: &Path) -> Vec<EventEnvelope> {
+    std::fs::read_to_string(path)
+        .map(|content| content.lines().filter_map(parse_event_line).collect())
+        .unwrap_or_default()
+}
+
+/// A page of a session's events, filtered by kind
+#[derive(Debug, Clone, Serialize)]
+pub struct TimelinePage {
+    pub entries: Vec<EventEnvelope>,
+    /// Number of events matching the filter, across all pages
+    pub total: usize,
+    pub offset: usize,
+}
+
+/// Select `limit` events starting at `offset` among those matching any of `kinds`
+/// (all events when `kinds` is empty).
+pub fn timeline_page(
+    events: Vec<EventEnvelope>,
+    offset: usize,
+    limit: usize,
+    kinds: &[String],
+) -> TimelinePage {
+    let matching: Vec<EventEnvelope> = events
+        .into_iter()
+        .filter(|e| kinds.is_empty() || kinds.iter().any(|k| e.event.matches_kind(k)))
+        .collect();
+    let total = matching.len();
+    TimelinePage {
+        entries: matching.into_iter().skip(offset).take(limit).collect(),
+        total,
+        offset,
+    }
+}
+
+/// Frontend events for one `events.jsonl` entry. A file-editing tool call also
+/// yields a `FileEdit` for the path it touches.
+pub fn to_session_events(event: &CopilotEvent) -> Vec<SessionEvent> {
+    match event {
+        CopilotEvent::UserMessage(data) if !data.content.is_empty() => {
+            vec![SessionEvent::Message {
+                role: "user".to_string(),
+                content: data.content.clone(),
+            }]
+        }
+        CopilotEvent::AssistantMessage(data) if !data.content.is_empty() => {
+            vec![SessionEvent::Message {
+                role: "assistant".to_string(),
+                content: data.content.clone(),
+            }]
+        }
+        CopilotEvent::ToolExecutionStart(data) => {
+            let mut events = vec![SessionEvent::ToolCall {
+                call_id: data.tool_call_id.clone(),
+                tool: data.tool_name.clone(),
+                arguments: data.arguments.clone(),
+            }];
+            let path = data.arguments.get("path").and_then(|p| p.as_str());
+            let edits_file = FILE_EDIT_TOOLS.contains(&data.tool_name.as_str());
+            if let (true, Some(path)) = (edits_file, path) {
+                events.push(SessionEvent::FileEdit {
+                    call_id: data.tool_call_id.clone(),
+                    path: path.to_string(),
+                });
+            }
+            events
+        }
+        CopilotEvent::ToolExecutionComplete(data) => vec![SessionEvent::ToolResult {
+            call_id: data.tool_call_id.clone(),
+            success: data.success,
+            output: match (&data.result, &data.error) {
+                (_, Some(error)) => error.message.clone(),
+                (Some(result), None) => result.content.clone(),
+                (None, None) => String::new(),
+            },
+        }],
+        CopilotEvent::Usage(data) => vec![SessionEvent::Usage {
+            model: data.model.clone(),
+            input_tokens: data.input_tokens,
+            output_tokens: data.output_tokens,
+        }],
+        CopilotEvent::Error(data) => vec![SessionEvent::Error {
+            message: data.message.clone(),
+        }],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_all(lines: &[&str]) -> Vec<EventEnvelope> {
+        lines.iter().filter_map(|l| parse_event_line(l)).collect()
+    }
+
+    fn ids(entries: &[EventEnvelope]) -> Vec<&str> {
+        entries.iter().map(|e| e.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_parse_tool_start_with_file_edit() {
+        let line = r#"{"type":"tool.execution_start","id":"e1","timestamp":"2025-10-01T10:00:00Z","data":{"toolCallId":"call_1","toolName":"edit","arguments":{"path":"/work/src/main.rs","old_str":"a","new_str":"b"}}}"#;
+        let envelope = parse_event_line(line).unwrap();
+        assert_eq!(envelope.id, "e1");
+
+        let events = to_session_events(&envelope.event);
+        assert_eq!(events.len(), 2);
+        assert_eq!(
+            events[1],
+            SessionEvent::FileEdit {
+                call_id: "call_1".to_string(),
+                path: "/work/src/main.rs".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_tool_completion_reports_error_message() {
+        let line = r#"{"type":"tool.execution_complete","id":"e2","timestamp":"t","data":{"toolCallId":"call_1","success":false,"error":{"message":"permission denied"}}}"#;
+        let events = to_session_events(&parse_event_line(line).unwrap().event);
+        assert_eq!(
+            events,
+            vec![SessionEvent::ToolResult {
+                call_id: "call_1".to_string(),
+                success: false,
+                output: "permission denied".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_unknown_event_kinds_keep_raw_data() {
+        let line = r#"{"type":"session.shutdown","id":"e3","timestamp":"t","data":{"reason":"exit"}}"#;
+        let envelope = parse_event_line(line).unwrap();
+        assert_eq!(envelope.event.kind(), "session.shutdown");
+        assert_eq!(
+            envelope.event,
+            CopilotEvent::Unknown {
+                kind: "session.shutdown".to_string(),
+                data: serde_json::json!({ "reason": "exit" })
+            }
+        );
+        assert!(to_session_events(&envelope.event).is_empty());
+        assert!(parse_event_line("{not json").is_none());
+    }
+
+    #[test]
+    fn test_unknown_events_serialize_with_their_kind() {
+        let line = r#"{"type":"session.shutdown","id":"e3","timestamp":"t","data":{"reason":"exit"}}"#;
+        let envelope = parse_event_line(line).unwrap();
+        let json = serde_json::to_value(&envelope).unwrap();
+        assert_eq!(json["type"], "session.shutdown");
+        assert_eq!(json["data"], serde_json::json!({ "reason": "exit" }));
+        assert!(json.get("kind").is_none());
+
+        let reparsed = parse_event_line(&json.to_string()).unwrap();
+        assert_eq!(reparsed.event, envelope.event);
+
+        // Known kinds keep their derived form
+        let known = r#"{"type":"abort","id":"e4","timestamp":"t","data":{"reason":"user"}}"#;
+        let json = serde_json::to_value(parse_event_line(known).unwrap()).unwrap();
+        assert_eq!(json["type"], "abort");
+        assert_eq!(json["data"]["reason"], "user");
+    }
+
+    #[test]
+    fn test_parse_known_kinds() {
+        let events = parse_all(&[
+            r#"{"type":"session.model_change","id":"a","timestamp":"t","data":{"newModel":"gpt-5"}}"#,
+            r#"{"type":"assistant.reasoning","id":"b","timestamp":"t","data":{"content":"Reading main.rs"}}"#,
+            r#"{"type":"session.compaction_complete","id":"c","timestamp":"t","data":{"success":true,"postCompactionTokens":12000}}"#,
+            r#"{"type":"assistant.turn_end","id":"d","timestamp":"t","parentId":"c"}"#,
+        ]);
+        assert!(matches!(&events[0].event, CopilotEvent::ModelChange(d) if d.new_model == "gpt-5"));
+        assert!(matches!(&events[1].event, CopilotEvent::Reasoning(d) if d.content.contains("main")));
+        assert!(matches!(
+            &events[2].event,
+            CopilotEvent::CompactionComplete(d) if d.post_compaction_tokens == Some(12000)
+        ));
+        assert_eq!(events[3].event, CopilotEvent::TurnEnd(TurnData::default()));
+        assert_eq!(events[3].parent_id.as_deref(), Some("c"));
+    }
+
+    #[test]
+    fn test_timeline_page_filters_by_kind_and_namespace() {
+        let lines = [
+            r#"{"type":"user.message","id":"1","timestamp":"t","data":{"content":"hi"}}"#,
+            r#"{"type":"tool.execution_start","id":"2","timestamp":"t","data":{"toolName":"bash"}}"#,
+            r#"{"type":"tool.execution_complete","id":"3","timestamp":"t","data":{"success":true}}"#,
+            r#"{"type":"assistant.message","id":"4","timestamp":"t","data":{"content":"done"}}"#,
+        ];
+
+        let page = timeline_page(parse_all(&lines), 1, 2, &[]);
+        assert_eq!(page.total, 4);
+        assert_eq!(ids(&page.entries), ["2", "3"]);
+
+        let tools = timeline_page(parse_all(&lines), 0, 10, &["tool".to_string()]);
+        assert_eq!(tools.total, 2);
+
+        let kinds = ["user.message".to_string(), "assistant.message".to_string()];
+        let messages = timeline_page(parse_all(&lines), 0, 10, &kinds);
+        assert_eq!(ids(&messages.entries), ["1", "4"]);
+
+        let partial = ["tool.execution".to_string()];
+        assert_eq!(timeline_page(parse_all(&lines), 0, 10, &partial).total, 0);
+    }
+
+    #[test]
+    fn test_envelope_serializes_flat() {
+        let line = r#"{"type":"abort","id":"9","timestamp":"t","data":{"reason":"user"}}"#;
+        let envelope = parse_event_line(line).unwrap();
+        assert_eq!(
+            serde_json::to_value(&envelope).unwrap(),
+            serde_json::json!({
+                "id": "9",
+                "timestamp": "t",
+                "parent_id": null,
+                "type": "abort",
+                "data": { "reason": "user" }
+            })
+        );
+    }
+}
@@ -1,23 +1,67 @@
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
-use crate::types::{AgentMode, SessionInfo};
+use super::store::{self, StoredSessions};
+use crate::types::{Account, AgentMode, CrashReport, SessionInfo};
 
 pub struct SessionManager {
     sessions: Mutex<HashMap<String, SessionInfo>>,
     active_session: Mutex<Option<String>>,
+    /// Where sessions are persisted; `None` keeps them in memory only
+    store_path: Option<PathBuf>,
 }
 
 impl SessionManager {
     pub fn new() -> Self {
+        let manager = Self::with_store(Some(store::default_store_path()));
+        manager.link_copilot_sessions();
+        manager
+    }
+
+    pub fn with_store(store_path: Option<PathBuf>) -> Self {
+        let stored = store_path
+            .as_deref()
+            .map(store::load_sessions)
+            .unwrap_or_default();
+
+        let sessions: HashMap<String, SessionInfo> = stored
+            .sessions
+            .into_iter()
+            .map(|s| (s.id.clone(), s))
+            .collect();
+        let active_session = stored
+            .active_session
+            .filter(|id| sessions.contains_key(id));
+
         Self {
-            sessions: Mutex::new(HashMap::new()),
-            active_session: Mutex::new(None),
+            sessions: Mutex::new(sessions),
+            active_session: Mutex::new(active_session),
+            store_path,
         }
     }
 
+    fn persist(&self) {
+        let Some(path) = &self.store_path else {
+            return;
+        };
+        let mut sessions: Vec<SessionInfo> = self
+            .sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .values()
+            .cloned()
+            .collect();
+        sessions.sort_by_key(|s| s.created_at);
+        let stored = StoredSessions {
+            active_session: self.get_active_session_id(),
+            sessions,
+        };
+        let _ = store::save_sessions(path, &stored);
+    }
+
     pub fn create_session(&self, name: &str, working_dir: &str) -> SessionInfo {
         let id = Uuid::new_v4().to_string();
         let now = SystemTime::now()
@@ -33,6 +77,9 @@ impl SessionManager {
             mode: AgentMode::Suggest,
             created_at: now,
             is_active: true,
+            copilot_session_id: None,
+            crash: None,
+            account: None,
         };
 
         self.sessions
@@ -41,6 +88,7 @@ impl SessionManager {
             .insert(id.clone(), session.clone());
 
         *self.active_session.lock().unwrap_or_else(|e| e.into_inner()) = Some(id);
+        self.persist();
 
         session
     }
@@ -64,14 +112,17 @@ impl SessionManager {
     }
 
     pub fn set_active_session(&self, session_id: &str) -> bool {
-        let sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
-        if sessions.contains_key(session_id) {
+        let exists = self
+            .sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains_key(session_id);
+        if exists {
             *self.active_session.lock().unwrap_or_else(|e| e.into_inner()) =
                 Some(session_id.to_string());
-            true
-        } else {
-            false
+            self.persist();
         }
+        exists
     }
 
     pub fn remove_session(&self, session_id: &str) -> bool {
@@ -87,6 +138,8 @@ impl SessionManager {
             if active.as_deref() == Some(session_id) {
                 *active = None;
             }
+            drop(active);
+            self.persist();
         }
         removed
     }
@@ -105,43 +158,99 @@ impl SessionManager {
     }
 
     pub fn rename_session(&self, session_id: &str, new_name: &str) -> bool {
-        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
-        if let Some(session) = sessions.get_mut(session_id) {
-            session.name = new_name.to_string();
-            true
-        } else {
-            false
-        }
+        self.update_session(session_id, |session| session.name = new_name.to_string())
     }
 
     pub fn set_session_model(&self, session_id: &str, model: &str) -> bool {
-        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
-        if let Some(session) = sessions.get_mut(session_id) {
-            session.model = Some(model.to_string());
-            true
-        } else {
-            false
-        }
+        self.update_session(session_id, |session| session.model = Some(model.to_string()))
+    }
+
+    pub fn set_session_account(&self, session_id: &str, account: Option<Account>) -> bool {
+        self.update_session(session_id, |session| session.account = account)
     }
 
     pub fn set_session_mode(&self, session_id: &str, mode: AgentMode) -> bool {
-        let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
-        if let Some(session) = sessions.get_mut(session_id) {
-            session.mode = mode;
-            true
-        } else {
-            false
+        self.update_session(session_id, |session| session.mode = mode)
+    }
+
+    pub fn set_copilot_session_id(&self, session_id: &str, copilot_session_id: &str) -> bool {
+        self.update_session(session_id, |session| {
+            session.copilot_session_id = Some(copilot_session_id.to_string())
+        })
+    }
+
+    /// Record (or with `None`, clear) the crash of a session's Copilot process.
+    pub fn set_crash_report(&self, session_id: &str, report: Option<CrashReport>) -> bool {
+        self.update_session(session_id, |session| session.crash = report)
+    }
+
+    /// Pair desktop sessions that have no Copilot CLI session id yet with the
+    /// CLI session created for the same working directory after they were opened.
+    pub fn link_copilot_sessions(&self) {
+        let Some(state_dir) = store::copilot_session_state_dir() else {
+            return;
+        };
+        let mut linked = false;
+        {
+            let mut sessions = self.sessions.lock().unwrap_or_else(|e| e.into_inner());
+            let mut claimed: Vec<String> = sessions
+                .values()
+                .filter_map(|s| s.copilot_session_id.clone())
+                .collect();
+            let mut unlinked: Vec<&mut SessionInfo> = sessions
+                .values_mut()
+                .filter(|s| s.copilot_session_id.is_none())
+                .collect();
+            unlinked.sort_by_key(|s| s.created_at);
+            for session in unlinked {
+                if let Some(id) = store::find_copilot_session(
+                    &state_dir,
+                    &session.working_dir,
+                    session.created_at,
+                    &claimed,
+                ) {
+                    claimed.push(id.clone());
+                    session.copilot_session_id = Some(id);
+                    linked = true;
+                }
+            }
+        }
+        if linked {
+            self.persist();
         }
     }
+
+    fn update_session(&self, session_id: &str, f: impl FnOnce(&mut SessionInfo)) -> bool {
+        let updated = match self
+            .sessions
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get_mut(session_id)
+        {
+            Some(session) => {
+                f(session);
+                true
+            }
+            None => false,
+        };
+        if updated {
+            self.persist();
+        }
+        updated
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn in_memory_manager() -> SessionManager {
+        SessionManager::with_store(None)
+    }
+
     #[test]
     fn test_create_session() {
-        let manager = SessionManager::new();
+        let manager = in_memory_manager();
         let session = manager.create_session("test", "/tmp");
         assert_eq!(session.name, "test");
         assert_eq!(session.working_dir, "/tmp");
@@ -151,7 +260,7 @@ mod tests {
 
     #[test]
     fn test_list_sessions() {
-        let manager = SessionManager::new();
+        let manager = in_memory_manager();
         manager.create_session("s1", "/tmp");
         manager.create_session("s2", "/home");
         let sessions = manager.list_sessions();
@@ -160,7 +269,7 @@ mod tests {
 
     #[test]
     fn test_active_session_is_last_created() {
-        let manager = SessionManager::new();
+        let manager = in_memory_manager();
         let s1 = manager.create_session("s1", "/tmp");
         let s2 = manager.create_session("s2", "/home");
         assert_eq!(manager.get_active_session_id(), Some(s2.id.clone()));
@@ -175,7 +284,7 @@ mod tests {
 
     #[test]
     fn test_delete_session() {
-        let manager = SessionManager::new();
+        let manager = in_memory_manager();
         let session = manager.create_session("test", "/tmp");
         assert!(manager.remove_session(&session.id));
         assert_eq!(manager.list_sessions().len(), 0);
@@ -183,7 +292,7 @@ mod tests {
 
     #[test]
     fn test_delete_session_clears_active() {
-        let manager = SessionManager::new();
+        let manager = in_memory_manager();
         let session = manager.create_session("test", "/tmp");
         manager.remove_session(&session.id);
         assert!(manager.get_active_session_id().is_none());
@@ -191,13 +300,13 @@ mod tests {
 
     #[test]
     fn test_delete_nonexistent_session_returns_false() {
-        let manager = SessionManager::new();
+        let manager = in_memory_manager();
         assert!(!manager.remove_session("nonexistent"));
     }
 
     #[test]
     fn test_set_active_session() {
-        let manager = SessionManager::new();
+        let manager = in_memory_manager();
         let s1 = manager.create_session("s1", "/tmp");
         let _s2 = manager.create_session("s2", "/home");
         assert!(manager.set_active_session(&s1.id));
@@ -206,10 +315,34 @@ mod tests {
 
     #[test]
     fn test_rename_session() {
-        let manager = SessionManager::new();
+        let manager = in_memory_manager();
         let session = manager.create_session("old", "/tmp");
         assert!(manager.rename_session(&session.id, "new"));
         let updated = manager.get_session(&session.id).unwrap();
         assert_eq!(updated.name, "new");
     }
+
+    #[test]
+    fn test_sessions_survive_restart() {
+        let dir = std::env::temp_dir().join(format!("copilot-test-{}", Uuid::new_v4()));
+        let path = dir.join("sessions.json");
+
+        let manager = SessionManager::with_store(Some(path.clone()));
+        let s1 = manager.create_session("s1", "/tmp");
+        let s2 = manager.create_session("s2", "/home");
+        manager.set_session_mode(&s1.id, AgentMode::AutoEdit);
+        manager.set_copilot_session_id(&s1.id, "cli-1");
+        manager.remove_session(&s2.id);
+        drop(manager);
+
+        let restored = SessionManager::with_store(Some(path));
+        let sessions = restored.list_sessions();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].name, "s1");
+        assert_eq!(sessions[0].mode, AgentMode::AutoEdit);
+        assert_eq!(sessions[0].copilot_session_id.as_deref(), Some("cli-1"));
+        assert!(restored.get_active_session_id().is_none());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
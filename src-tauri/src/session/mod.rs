@@ -1,3 +1,7 @@
+pub mod events;
 mod manager;
+pub mod store;
+mod tail;
 
 pub use manager::SessionManager;
+pub use tail::EventStreams;
@@ -0,0 +1,190 @@
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::types::{CopilotSession, SessionInfo};
+
+/// On-disk format of `~/.copilot-desktop/sessions.json`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct StoredSessions {
+    #[serde(default)]
+    pub active_session: Option<String>,
+    #[serde(default)]
+    pub sessions: Vec<SessionInfo>,
+}
+
+pub fn default_store_path() -> PathBuf {
+    let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+    home.join(".copilot-desktop").join("sessions.json")
+}
+
+pub fn load_sessions(path: &Path) -> StoredSessions {
+    match std::fs::read_to_string(path) {
+        Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+        Err(_) => StoredSessions::default(),
+    }
+}
+
+pub fn save_sessions(path: &Path, stored: &StoredSessions) -> Result<(), std::io::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let data = serde_json::to_string_pretty(stored)
+        .map_err(std::io::Error::other)?;
+    // Write to a sibling temp file first so a crash mid-write never truncates the store
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Directory where the Copilot CLI keeps one folder per session (`~/.copilot/session-state`)
+pub fn copilot_session_state_dir() -> Option<PathBuf> {
+    dirs::home_dir().map(|h| h.join(".copilot").join("session-state"))
+}
+
+/// Event log the Copilot CLI appends to while a session runs
+pub fn copilot_events_path(copilot_session_id: &str) -> Option<PathBuf> {
+    copilot_session_state_dir().map(|dir| dir.join(copilot_session_id).join("events.jsonl"))
+}
+
+pub fn parse_workspace_yaml(content: &str) -> Option<CopilotSession> {
+    let mut id = String::new();
+    let mut cwd = String::new();
+    let mut summary = None;
+    let mut repository = None;
+    let mut branch = None;
+    let mut created_at = String::new();
+    let mut updated_at = String::new();
+
+    for line in content.lines() {
+        if let Some((key, value)) = line.split_once(": ") {
+            let value = value.trim();
+            match key.trim() {
+                "id" => id = value.to_string(),
+                "cwd" => cwd = value.to_string(),
+                "summary" => summary = Some(value.to_string()),
+                "repository" => repository = Some(value.to_string()),
+                "branch" => branch = Some(value.to_string()),
+                "created_at" => created_at = value.to_string(),
+                "updated_at" => updated_at = value.to_string(),
+                _ => {}
+            }
+        }
+    }
+
+    if id.is_empty() || cwd.is_empty() {
+        return None;
+    }
+    Some(CopilotSession { id, cwd, summary, repository, branch, created_at, updated_at })
+}
+
+/// Find the Copilot CLI session that was started for `working_dir` at or after `since`
+/// (unix seconds), skipping ids already linked to another desktop session.
+/// The oldest matching session wins, so desktop sessions opened in the same directory
+/// are paired with CLI sessions in creation order.
+pub fn find_copilot_session(
+    state_dir: &Path,
+    working_dir: &str,
+    since: u64,
+    claimed: &[String],
+) -> Option<String> {
+    let entries = std::fs::read_dir(state_dir).ok()?;
+
+    entries
+        .filter_map(|entry| {
+            let dir = entry.ok()?.path();
+            let content = std::fs::read_to_string(dir.join("workspace.yaml")).ok()?;
+            let copilot_session = parse_workspace_yaml(&content)?;
+            if copilot_session.cwd != working_dir || claimed.contains(&copilot_session.id) {
+                return None;
+            }
+            let metadata = std::fs::metadata(&dir).ok()?;
+            let created = metadata
+                .created()
+                .or_else(|_| metadata.modified())
+                .ok()?
+                .duration_since(UNIX_EPOCH)
+                .ok()?
+                .as_secs();
+            (created >= since).then_some((created, copilot_session.id))
+        })
+        .min()
+        .map(|(_, id)| id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::AgentMode;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("copilot-test-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_workspace(state_dir: &Path, id: &str, cwd: &str) {
+        let dir = state_dir.join(id);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("workspace.yaml"), format!("id: {id}\ncwd: {cwd}\n")).unwrap();
+    }
+
+    #[test]
+    fn test_save_load_roundtrip() {
+        let dir = temp_dir();
+        let path = dir.join("sessions.json");
+        let stored = StoredSessions {
+            active_session: Some("a".to_string()),
+            sessions: vec![SessionInfo {
+                id: "a".to_string(),
+                name: "project".to_string(),
+                working_dir: "/tmp".to_string(),
+                model: Some("gpt-5".to_string()),
+                mode: AgentMode::Autopilot,
+                created_at: 42,
+                is_active: true,
+                copilot_session_id: Some("cli-1".to_string()),
+                crash: None,
+                account: None,
+            }],
+        };
+        save_sessions(&path, &stored).unwrap();
+
+        let loaded = load_sessions(&path);
+        assert_eq!(loaded.active_session.as_deref(), Some("a"));
+        assert_eq!(loaded.sessions.len(), 1);
+        assert_eq!(loaded.sessions[0].mode, AgentMode::Autopilot);
+        assert_eq!(loaded.sessions[0].copilot_session_id.as_deref(), Some("cli-1"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_load_missing_or_corrupt_store() {
+        let dir = temp_dir();
+        assert!(load_sessions(&dir.join("missing.json")).sessions.is_empty());
+
+        let path = dir.join("sessions.json");
+        std::fs::write(&path, "not json").unwrap();
+        assert!(load_sessions(&path).sessions.is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_find_copilot_session_matches_cwd_and_skips_claimed() {
+        let state_dir = temp_dir();
+        write_workspace(&state_dir, "cli-1", "/work/a");
+        write_workspace(&state_dir, "cli-2", "/work/b");
+
+        assert_eq!(
+            find_copilot_session(&state_dir, "/work/b", 0, &[]).as_deref(),
+            Some("cli-2")
+        );
+        assert!(find_copilot_session(&state_dir, "/work/a", 0, &["cli-1".to_string()]).is_none());
+        assert!(find_copilot_session(&state_dir, "/work/a", u64::MAX, &[]).is_none());
+
+        let _ = std::fs::remove_dir_all(&state_dir);
+    }
+}
@@ -0,0 +1,299 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use tauri::{AppHandle, Emitter, Manager};
+
+use super::events::{parse_event_line, to_session_events, EventEnvelope};
+use super::store;
+use super::SessionManager;
+use crate::auth;
+use crate::config::ConfigManager;
+use crate::models::{availability_from_event, ModelCatalog};
+use crate::pty::PtyManager;
+use crate::types::SessionEventRecord;
+use crate::usage::UsageTracker;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// How often to look for the CLI session of a desktop session that isn't linked yet
+const LINK_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Follows a growing JSONL file, returning only complete lines.
+pub struct EventTailer {
+    path: PathBuf,
+    offset: u64,
+    partial: Vec<u8>,
+}
+
+impl EventTailer {
+    /// Read the file from its first line, including lines written before the tailer existed.
+    pub fn from_start(path: PathBuf) -> Self {
+        Self {
+            path,
+            offset: 0,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Skip what the file already holds and only return lines appended from now on.
+    pub fn from_end(path: PathBuf) -> Self {
+        let offset = std::fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+        Self {
+            path,
+            offset,
+            partial: Vec::new(),
+        }
+    }
+
+    /// Lines completed since the last call. A file that shrank (rotated or rewritten)
+    /// is read again from the beginning.
+    pub fn poll(&mut self) -> Vec<String> {
+        let Ok(mut file) = File::open(&self.path) else {
+            return Vec::new();
+        };
+        let len = file.metadata().map(|m| m.len()).unwrap_or(0);
+        if len < self.offset {
+            self.offset = 0;
+            self.partial.clear();
+        }
+        if len == self.offset || file.seek(SeekFrom::Start(self.offset)).is_err() {
+            return Vec::new();
+        }
+
+        let mut buf = Vec::new();
+        if file.read_to_end(&mut buf).is_err() {
+            return Vec::new();
+        }
+        self.offset += buf.len() as u64;
+        self.partial.extend_from_slice(&buf);
+
+        let Some(end) = self.partial.iter().rposition(|&b| b == b'\n') else {
+            return Vec::new();
+        };
+        let complete: Vec<u8> = self.partial.drain(..=end).collect();
+        String::from_utf8_lossy(&complete)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|line| line.to_string())
+            .collect()
+    }
+}
+
+/// Entries of a tailed event log, each returned once. A log the tailer reads again from
+/// the start after it was rewritten would otherwise be counted and shown twice.
+struct EventLog {
+    tailer: EventTailer,
+    seen: HashSet<String>,
+}
+
+impl EventLog {
+    fn new(tailer: EventTailer) -> Self {
+        Self {
+            tailer,
+            seen: HashSet::new(),
+        }
+    }
+
+    fn poll(&mut self) -> Vec<EventEnvelope> {
+        let seen = &mut self.seen;
+        self.tailer
+            .poll()
+            .iter()
+            .filter_map(|line| parse_event_line(line))
+            .filter(|envelope| envelope.id.is_empty() || seen.insert(envelope.id.clone()))
+            .collect()
+    }
+}
+
+/// Live `events.jsonl` readers, one per running desktop session.
+pub struct EventStreams {
+    streams: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl EventStreams {
+    pub fn new() -> Self {
+        Self {
+            streams: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Follow the event log of the session's Copilot CLI session and emit each new
+    /// entry as `session-event-{id}`. Once events arrive, the PTY parser's tool and
+    /// file heuristics are switched off for the session.
+    pub fn start(&self, session_id: &str, app_handle: AppHandle) {
+        let stop = Arc::new(AtomicBool::new(false));
+        if let Some(previous) = self
+            .streams
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(session_id.to_string(), stop.clone())
+        {
+            previous.store(true, Ordering::Relaxed);
+        }
+
+        let sid = session_id.to_string();
+        std::thread::spawn(move || follow_events(&sid, &stop, &app_handle));
+    }
+
+    pub fn stop(&self, session_id: &str) {
+        if let Some(stop) = self
+            .streams
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(session_id)
+        {
+            stop.store(true, Ordering::Relaxed);
+        }
+    }
+}
+
+fn follow_events(session_id: &str, stop: &AtomicBool, app_handle: &AppHandle) {
+    let event_name = format!("session-event-{}", session_id);
+    let session_mgr = app_handle.state::<SessionManager>();
+    let pty = app_handle.state::<PtyManager>();
+    let config = app_handle.state::<ConfigManager>();
+    let usage = app_handle.state::<UsageTracker>();
+    let catalog = app_handle.state::<ModelCatalog>();
+
+    // A CLI session linked up front is being resumed, so its history is already on disk
+    let mut tailer = session_mgr
+        .get_session(session_id)
+        .and_then(|s| s.copilot_session_id)
+        .and_then(|id| store::copilot_events_path(&id))
+        .map(|path| EventLog::new(EventTailer::from_end(path)));
+    let mut last_link = Instant::now();
+    let mut structured = false;
+    let known_models = catalog.list(None, None, false);
+
+    loop {
+        // Read once more after being stopped, so entries the CLI wrote just before
+        // exiting still reach the UI
+        let stopping = stop.load(Ordering::Relaxed);
+        if tailer.is_none() && last_link.elapsed() >= LINK_INTERVAL {
+            last_link = Instant::now();
+            session_mgr.link_copilot_sessions();
+            let Some(linked) = session_mgr.get_session(session_id) else {
+                return;
+            };
+            tailer = linked
+                .copilot_session_id
+                .and_then(|id| store::copilot_events_path(&id))
+                .map(|path| EventLog::new(EventTailer::from_start(path)));
+        }
+
+        let envelopes = tailer.as_mut().map(EventLog::poll).unwrap_or_default();
+        // Read per poll: a restart for a model, mode or account switch keeps this stream
+        let Some(session) = session_mgr.get_session(session_id) else {
+            return;
+        };
+        for envelope in &envelopes {
+            let warning = usage.record(
+                session_id,
+                &session.working_dir,
+                session.model.as_deref(),
+                envelope,
+                &config.get_config().usage,
+            );
+            if let Some(warning) = warning {
+                let _ = app_handle.emit("usage-warning", warning);
+            }
+            let seen = availability_from_event(&envelope.event, &known_models);
+            if let Some((model, available)) = seen {
+                let account = session.account.clone().or_else(auth::active_account);
+                if let Some(account) = account {
+                    catalog.set_available(&account, &model, available);
+                }
+            }
+            for event in to_session_events(&envelope.event) {
+                if !structured {
+                    structured = pty.set_structured_events(session_id).is_ok();
+                }
+                let record = SessionEventRecord {
+                    id: envelope.id.clone(),
+                    timestamp: envelope.timestamp.clone(),
+                    event,
+                };
+                let _ = app_handle.emit(&event_name, record);
+            }
+        }
+        if !envelopes.is_empty() {
+            usage.save();
+        }
+
+        if stopping {
+            break;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp_file() -> PathBuf {
+        std::env::temp_dir().join(format!("copilot-events-{}.jsonl", uuid::Uuid::new_v4()))
+    }
+
+    fn append(path: &PathBuf, data: &str) {
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(data.as_bytes()).unwrap();
+    }
+
+    #[test]
+    fn test_tailer_returns_complete_lines_only() {
+        let path = temp_file();
+        let mut tailer = EventTailer::from_start(path.clone());
+        assert!(tailer.poll().is_empty());
+
+        append(&path, "{\"a\":1}\n{\"b\":");
+        assert_eq!(tailer.poll(), vec!["{\"a\":1}"]);
+        append(&path, "2}\n");
+        assert_eq!(tailer.poll(), vec!["{\"b\":2}"]);
+        assert!(tailer.poll().is_empty());
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_tailer_from_end_skips_history_and_handles_truncation() {
+        let path = temp_file();
+        append(&path, "old\n");
+        let mut tailer = EventTailer::from_end(path.clone());
+        append(&path, "new\n");
+        assert_eq!(tailer.poll(), vec!["new"]);
+
+        std::fs::write(&path, "x\n").unwrap();
+        assert_eq!(tailer.poll(), vec!["x"]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn test_event_log_skips_entries_replayed_after_rewrite() {
+        let path = temp_file();
+        let first = r#"{"id":"e1","timestamp":"2025-01-01T00:00:00Z","type":"user.message","data":{"content":"hi"}}"#;
+        let second = r#"{"id":"e2","timestamp":"2025-01-01T00:00:01Z","type":"user.message","data":{"content":"again"}}"#;
+        append(&path, &format!("{first}\n{second}\n"));
+        let mut log = EventLog::new(EventTailer::from_start(path.clone()));
+        let ids: Vec<String> = log.poll().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, ["e1", "e2"]);
+
+        // Rewritten shorter: the tailer starts over, but only the new entry comes back
+        let third = r#"{"id":"e3","timestamp":"2025-01-01T00:00:02Z","type":"user.message","data":{"content":"new"}}"#;
+        std::fs::write(&path, format!("{first}\n{third}\n")).unwrap();
+        let ids: Vec<String> = log.poll().into_iter().map(|e| e.id).collect();
+        assert_eq!(ids, ["e3"]);
+
+        let _ = std::fs::remove_file(&path);
+    }
+}
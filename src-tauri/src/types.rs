@@ -34,6 +34,16 @@ pub struct CopilotMessage {
     pub timestamp: u64,
 }
 
+/// A user or assistant message from a Copilot CLI session's history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionMessage {
+    pub id: String,
+    pub role: MessageRole,
+    pub content: String,
+    /// ISO 8601 timestamp as written by the CLI
+    pub timestamp: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum MessageRole {
@@ -56,6 +66,12 @@ pub struct ModelInfo {
     pub name: String,
     pub provider: String,
     pub description: String,
+    /// Premium requests consumed per prompt; 0 for models included in the plan
+    #[serde(default)]
+    pub premium_multiplier: Option<f32>,
+    /// Whether the signed-in account was seen using (or being refused) this model
+    #[serde(default)]
+    pub available: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,13 +83,79 @@ pub struct SessionInfo {
     pub mode: AgentMode,
     pub created_at: u64,
     pub is_active: bool,
+    /// Copilot CLI session id under `~/.copilot/session-state/`, used to `--resume`
+    #[serde(default)]
+    pub copilot_session_id: Option<String>,
+    /// Set when the Copilot process exited unexpectedly; cleared once it runs again
+    #[serde(default)]
+    pub crash: Option<CrashReport>,
+    /// Account the CLI signs in with for this session; `None` uses the CLI's current one
+    #[serde(default)]
+    pub account: Option<Account>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// How a Copilot process ended when it exited unexpectedly
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CrashReport {
+    pub code: Option<i32>,
+    pub signal: Option<String>,
+    /// How long the process ran, in milliseconds
+    pub duration: u64,
+    /// Unix timestamp (seconds)
+    pub crashed_at: u64,
+    /// Last lines of terminal output before the exit, without ANSI escapes
+    pub last_lines: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct AuthStatus {
     pub authenticated: bool,
     pub username: Option<String>,
     pub email: Option<String>,
+    /// Server of the active account, e.g. `https://github.com` or a GHES URL
+    pub host: Option<String>,
+}
+
+/// Payload of the `copilot-config-changed` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CopilotConfigChange {
+    /// Model selected in the CLI, if any
+    pub model: Option<String>,
+}
+
+/// Device-flow code the user enters at `verification_uri` to finish `copilot login`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LoginChallenge {
+    pub user_code: String,
+    pub verification_uri: String,
+}
+
+/// Progress of a login started with `trigger_login`, emitted on `login-event`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum LoginEvent {
+    Challenge(LoginChallenge),
+    Succeeded(AuthStatus),
+    /// The CLI exited without signing in; carries its last line of output
+    Failed(String),
+    TimedOut,
+}
+
+/// A user logged in to the Copilot CLI
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Account {
+    pub host: String,
+    pub login: String,
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+impl Account {
+    /// Same login on the same server
+    pub fn same_user(&self, other: &Account) -> bool {
+        self.login == other.login
+            && self.host.trim_end_matches('/') == other.host.trim_end_matches('/')
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -82,6 +164,12 @@ pub struct CopilotStatus {
     pub path: Option<String>,
     pub version: Option<String>,
     pub authenticated: bool,
+    /// False when the CLI's `--help` lacks a flag every session needs
+    pub supported: bool,
+    /// Flags the app uses that this CLI's `--help` doesn't list
+    pub unsupported_flags: Vec<String>,
+    /// Why the configured `copilot_path` isn't used, when it can't be
+    pub error: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -90,10 +178,68 @@ pub enum PtyEvent {
     Output(String),
     Parsed(ParsedOutput),
     Error(String),
-    Exit(i32),
+    /// The CLI process ended. `code` is `None` when it was killed by a signal;
+    /// `duration` is how long the process ran, in milliseconds.
+    SessionExited {
+        code: Option<i32>,
+        signal: Option<String>,
+        duration: u64,
+    },
+    /// Follows `SessionExited` when the exit was unexpected. `restart_in` is the
+    /// delay in milliseconds before an automatic restart, if one is scheduled.
+    SessionCrashed {
+        report: CrashReport,
+        restart_in: Option<u64>,
+    },
+}
+
+/// Named keys accepted by `send_keys`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum NamedKey {
+    Enter,
+    Tab,
+    Esc,
+    Backspace,
+    Space,
+    Up,
+    Down,
+    Left,
+    Right,
+    #[serde(rename = "Ctrl-C")]
+    CtrlC,
+    #[serde(rename = "Ctrl-D")]
+    CtrlD,
+}
+
+/// One element of a `send_keys` sequence
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum KeyInput {
+    Key(NamedKey),
+    Text(String),
+    Bytes(Vec<u8>),
 }
 
+/// A `PtyEvent` as emitted on `pty-output-{id}`, tagged with its per-session sequence number
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SequencedPtyEvent {
+    pub seq: u64,
+    #[serde(flatten)]
+    pub event: PtyEvent,
+}
+
+/// Scrollback replay returned by `get_session_output`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionOutput {
+    pub events: Vec<SequencedPtyEvent>,
+    pub raw: String,
+    /// Sequence number the next live event will carry
+    pub next_seq: u64,
+    /// True when events older than `since_seq` have already been discarded
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(tag = "type", content = "data")]
 pub enum ParsedOutput {
     Text(String),
@@ -103,6 +249,31 @@ pub enum ParsedOutput {
     Thinking(String),
     Error(String),
     RawLine(String),
+    /// Copilot is waiting for the user to approve a tool call; answer with `respond_to_permission`
+    PermissionRequest { tool: String, command: String, options: Vec<String> },
+}
+
+/// Structured event read live from a session's `events.jsonl`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", content = "data")]
+pub enum SessionEvent {
+    Message { role: String, content: String },
+    ToolCall { call_id: String, tool: String, arguments: serde_json::Value },
+    ToolResult { call_id: String, success: bool, output: String },
+    /// A tool call that creates or modifies `path`
+    FileEdit { call_id: String, path: String },
+    Usage { model: Option<String>, input_tokens: Option<u64>, output_tokens: Option<u64> },
+    Error { message: String },
+}
+
+/// Payload of the `session-event-{id}` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionEventRecord {
+    /// Id of the `events.jsonl` entry the event came from
+    pub id: String,
+    pub timestamp: String,
+    #[serde(flatten)]
+    pub event: SessionEvent,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -155,25 +326,193 @@ pub struct PluginInfo {
     pub downloads: Option<u64>,
 }
 
+/// How the CLI talks to an MCP server, the `type` key of its config entry
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum McpTransport {
+    /// A process the CLI starts, speaking MCP over stdio
+    #[default]
+    #[serde(alias = "stdio")]
+    Local,
+    /// A remote server speaking streamable HTTP
+    Http,
+    /// A remote server speaking HTTP with server-sent events
+    Sse,
+}
+
+/// Which config file an MCP server comes from
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum McpScope {
+    /// `~/.copilot/mcp-config.json`, for every session
+    #[default]
+    Global,
+    /// `.copilot/mcp-config.json` in the session's working directory. Its servers
+    /// take precedence over global ones with the same name.
+    Project,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct McpServerConfig {
     pub name: String,
+    #[serde(default)]
+    pub scope: McpScope,
+    #[serde(default)]
+    pub transport: McpTransport,
+    /// Local servers only
+    #[serde(default)]
     pub command: String,
+    #[serde(default)]
     pub args: Vec<String>,
+    /// Values may be `${env:NAME}` or `${file:path}` references, resolved when a
+    /// session starts. `list_mcp_servers` shows credential-like literals redacted.
     pub env: Option<HashMap<String, String>>,
+    /// Remote servers only
+    #[serde(default)]
+    pub url: Option<String>,
+    /// Remote servers only. Values may use the same references as `env`, so tokens
+    /// needn't be stored in the config.
+    #[serde(default)]
+    pub headers: Option<HashMap<String, String>>,
     pub enabled: bool,
     pub status: Option<String>,
+    /// Set by `list_mcp_servers`, e.g. for tokens stored in plain text
+    #[serde(default)]
+    pub warnings: Vec<String>,
+}
+
+/// Outcome of the last `probe_mcp_server`, reported as `McpServerConfig.status`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum McpHealth {
+    Healthy,
+    Failing,
+}
+
+impl McpHealth {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            McpHealth::Healthy => "healthy",
+            McpHealth::Failing => "failing",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpTool {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpResource {
+    pub uri: String,
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default, alias = "mimeType")]
+    pub mime_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct McpPrompt {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+/// What `probe_mcp_server` found when it started a server and talked to it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpProbeResult {
+    pub name: String,
+    pub health: McpHealth,
+    /// `serverInfo` from the `initialize` response, e.g. `github-mcp-server 0.5.0`
+    pub server_info: Option<String>,
+    pub protocol_version: Option<String>,
+    /// Time from launch to the `initialize` response, in milliseconds
+    pub startup_ms: Option<u64>,
+    pub tools: Vec<McpTool>,
+    pub resources: Vec<McpResource>,
+    pub prompts: Vec<McpPrompt>,
+    pub error: Option<String>,
+    /// Last lines the server wrote to stderr
+    pub stderr_tail: Vec<String>,
+    /// Unix timestamp (seconds)
+    pub checked_at: u64,
+}
+
+/// Another tool whose MCP config can be imported
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum McpImportSource {
+    #[serde(rename = "vscode")]
+    VsCode,
+    #[serde(rename = "claude-desktop")]
+    ClaudeDesktop,
+    #[serde(rename = "cursor")]
+    Cursor,
+}
+
+impl McpImportSource {
+    pub fn label(self) -> &'static str {
+        match self {
+            McpImportSource::VsCode => "VS Code",
+            McpImportSource::ClaudeDesktop => "Claude Desktop",
+            McpImportSource::Cursor => "Cursor",
+        }
+    }
+}
+
+/// A server found by `preview_mcp_import`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct McpImportCandidate {
+    pub source: McpImportSource,
+    /// Config file it was read from
+    pub path: String,
+    pub server: McpServerConfig,
+    /// Scope of the existing server that has this name, if any
+    pub conflict: Option<McpScope>,
+    /// What didn't carry over, e.g. VS Code `${input:...}` prompts
+    pub warnings: Vec<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsageMetrics {
-    pub premium_requests_used: u32,
+    pub premium_requests_used: f64,
     pub premium_requests_limit: Option<u32>,
     pub session_messages: u32,
     pub session_tokens: Option<u64>,
     pub active_model: String,
 }
 
+/// Usage totals for one day, project or model
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct UsageBucket {
+    pub key: String,
+    pub premium_requests: f64,
+    pub messages: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageSummary {
+    pub by_day: Vec<UsageBucket>,
+    pub by_project: Vec<UsageBucket>,
+    pub by_model: Vec<UsageBucket>,
+    /// Premium requests used in the current calendar month
+    pub month_premium_requests: f64,
+    pub premium_requests_limit: Option<u32>,
+}
+
+/// Payload of the `usage-warning` event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageWarning {
+    pub used: f64,
+    pub limit: u32,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct KeyboardShortcut {
     pub key: String,
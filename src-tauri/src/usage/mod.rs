@@ -0,0 +1,477 @@
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::UsageSettings;
+use crate::models;
+use crate::session::events::{CopilotEvent, EventEnvelope};
+use crate::types::{UsageBucket, UsageSummary, UsageWarning};
+
+/// Usage of one model in one project on one day
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+pub struct UsageRecord {
+    /// `YYYY-MM-DD` (UTC)
+    pub date: String,
+    pub project: String,
+    pub model: String,
+    /// Premium requests charged for the prompts sent to the model: one per prompt
+    /// times the model's multiplier, so included models count nothing
+    pub premium_requests: f64,
+    /// User and assistant messages
+    pub messages: u32,
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+}
+
+/// On-disk format of `~/.copilot-desktop/usage.json`
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct UsageHistory {
+    #[serde(default)]
+    pub records: Vec<UsageRecord>,
+    /// Month (`YYYY-MM`) for which the quota warning was already sent
+    #[serde(default)]
+    pub warned_month: Option<String>,
+}
+
+impl UsageHistory {
+    fn record_mut(&mut self, date: &str, project: &str, model: &str) -> &mut UsageRecord {
+        let index = match self
+            .records
+            .iter()
+            .position(|r| r.date == date && r.project == project && r.model == model)
+        {
+            Some(index) => index,
+            None => {
+                self.records.push(UsageRecord {
+                    date: date.to_string(),
+                    project: project.to_string(),
+                    model: model.to_string(),
+                    ..UsageRecord::default()
+                });
+                self.records.len() - 1
+            }
+        };
+        &mut self.records[index]
+    }
+
+    /// Count one event. Returns false when the event doesn't affect usage.
+    pub fn apply(&mut self, project: &str, model: &str, envelope: &EventEnvelope) -> bool {
+        let date = event_date(&envelope.timestamp);
+        match &envelope.event {
+            CopilotEvent::UserMessage(data) if !data.content.is_empty() => {
+                // Models whose multiplier we don't know are charged as one request
+                let multiplier = models::describe(model).premium_multiplier.map_or(1.0, f64::from);
+                let record = self.record_mut(&date, project, model);
+                record.premium_requests += multiplier;
+                record.messages += 1;
+            }
+            CopilotEvent::AssistantMessage(data) if !data.content.is_empty() => {
+                self.record_mut(&date, project, model).messages += 1;
+            }
+            CopilotEvent::Usage(data) => {
+                let model = data.model.as_deref().unwrap_or(model);
+                let record = self.record_mut(&date, project, model);
+                record.input_tokens += data.input_tokens.unwrap_or(0);
+                record.output_tokens += data.output_tokens.unwrap_or(0);
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    pub fn premium_requests_in_month(&self, month: &str) -> f64 {
+        self.records
+            .iter()
+            .filter(|r| r.date.starts_with(month))
+            .map(|r| r.premium_requests)
+            .sum()
+    }
+
+    /// Totals per day, project and model for records dated `since` (`YYYY-MM-DD`) or later.
+    pub fn summary(&self, since: &str, month: &str, limit: Option<u32>) -> UsageSummary {
+        let mut by_day = BTreeMap::new();
+        let mut by_project = BTreeMap::new();
+        let mut by_model = BTreeMap::new();
+        for record in self.records.iter().filter(|r| r.date.as_str() >= since) {
+            add_to_bucket(&mut by_day, &record.date, record);
+            add_to_bucket(&mut by_project, &record.project, record);
+            add_to_bucket(&mut by_model, &record.model, record);
+        }
+        UsageSummary {
+            by_day: by_day.into_values().collect(),
+            by_project: by_project.into_values().collect(),
+            by_model: by_model.into_values().collect(),
+            month_premium_requests: self.premium_requests_in_month(month),
+            premium_requests_limit: limit,
+        }
+    }
+}
+
+fn add_to_bucket(buckets: &mut BTreeMap<String, UsageBucket>, key: &str, record: &UsageRecord) {
+    let bucket = buckets
+        .entry(key.to_string())
+        .or_insert_with(|| UsageBucket {
+            key: key.to_string(),
+            ..UsageBucket::default()
+        });
+    bucket.premium_requests += record.premium_requests;
+    bucket.messages += record.messages;
+    bucket.input_tokens += record.input_tokens;
+    bucket.output_tokens += record.output_tokens;
+}
+
+/// Message count, reported tokens and last model of one session's event log
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionUsage {
+    pub messages: u32,
+    pub tokens: Option<u64>,
+    pub model: Option<String>,
+}
+
+impl SessionUsage {
+    pub fn from_events(events: &[EventEnvelope]) -> Self {
+        let mut usage = SessionUsage::default();
+        for envelope in events {
+            match &envelope.event {
+                CopilotEvent::UserMessage(data) if !data.content.is_empty() => usage.messages += 1,
+                CopilotEvent::AssistantMessage(data) if !data.content.is_empty() => {
+                    usage.messages += 1
+                }
+                CopilotEvent::ModelChange(data) => usage.model = Some(data.new_model.clone()),
+                CopilotEvent::Usage(data) => {
+                    let tokens = data.input_tokens.unwrap_or(0) + data.output_tokens.unwrap_or(0);
+                    usage.tokens = Some(usage.tokens.unwrap_or(0) + tokens);
+                    if let Some(model) = &data.model {
+                        usage.model = Some(model.clone());
+                    }
+                }
+                _ => {}
+            }
+        }
+        usage
+    }
+}
+
+/// Records usage from live session events and keeps the persisted history.
+pub struct UsageTracker {
+    history: Mutex<UsageHistory>,
+    /// Model currently in use per desktop session, as last reported by its events
+    models: Mutex<HashMap<String, String>>,
+    store_path: Option<PathBuf>,
+    /// History changed since the last `save`
+    dirty: AtomicBool,
+}
+
+impl UsageTracker {
+    pub fn new() -> Self {
+        let home = dirs::home_dir().unwrap_or_else(|| PathBuf::from("."));
+        Self::with_store(Some(home.join(".copilot-desktop").join("usage.json")))
+    }
+
+    /// A tracker persisting to `store_path`; `None` keeps history in memory only.
+    pub fn with_store(store_path: Option<PathBuf>) -> Self {
+        let history = store_path.as_deref().map(load_history).unwrap_or_default();
+        Self {
+            history: Mutex::new(history),
+            models: Mutex::new(HashMap::new()),
+            store_path,
+            dirty: AtomicBool::new(false),
+        }
+    }
+
+    /// Count an event from a session's live event stream. Returns a warning the first
+    /// time in a month the premium requests used reach the configured threshold. The
+    /// history is written to disk by `save`.
+    pub fn record(
+        &self,
+        session_id: &str,
+        project: &str,
+        session_model: Option<&str>,
+        envelope: &EventEnvelope,
+        settings: &UsageSettings,
+    ) -> Option<UsageWarning> {
+        let model = {
+            let mut models = self.models.lock().unwrap_or_else(|e| e.into_inner());
+            match &envelope.event {
+                CopilotEvent::ModelChange(data) => {
+                    models.insert(session_id.to_string(), data.new_model.clone());
+                }
+                CopilotEvent::Usage(data) => {
+                    if let Some(model) = &data.model {
+                        models.insert(session_id.to_string(), model.clone());
+                    }
+                }
+                _ => {}
+            }
+            models
+                .get(session_id)
+                .cloned()
+                .or_else(|| session_model.map(|m| m.to_string()))
+                .unwrap_or_else(|| "default".to_string())
+        };
+
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        if !history.apply(project, &model, envelope) {
+            return None;
+        }
+        self.dirty.store(true, Ordering::Relaxed);
+
+        let month = current_month();
+        let warning = settings.premium_request_limit.and_then(|limit| {
+            let used = history.premium_requests_in_month(&month);
+            let threshold = f64::from(limit) * f64::from(settings.warn_at_percent) / 100.0;
+            if used < threshold || history.warned_month.as_deref() == Some(&month) {
+                return None;
+            }
+            history.warned_month = Some(month.clone());
+            Some(UsageWarning { used, limit })
+        });
+
+        warning
+    }
+
+    /// Persist the history if `record` changed it since the last save.
+    pub fn save(&self) {
+        if !self.dirty.swap(false, Ordering::Relaxed) {
+            return;
+        }
+        if let Some(path) = &self.store_path {
+            let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+            let _ = save_history(path, &history);
+        }
+    }
+
+    /// Forget the model a session's events last reported, when its process is replaced
+    /// and starts on the model it was spawned with.
+    pub fn reset_session(&self, session_id: &str) {
+        self.models
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(session_id);
+    }
+
+    pub fn premium_requests_this_month(&self) -> f64 {
+        self.history
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .premium_requests_in_month(&current_month())
+    }
+
+    /// Usage over the last `days` days, including today.
+    pub fn summary(&self, days: u32, limit: Option<u32>) -> UsageSummary {
+        let since = date_from_days(today_days() - days.saturating_sub(1) as i64);
+        self.history
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .summary(&since, &current_month(), limit)
+    }
+}
+
+fn load_history(path: &Path) -> UsageHistory {
+    match std::fs::read_to_string(path) {
+        Ok(data) => serde_json::from_str(&data).unwrap_or_default(),
+        Err(_) => UsageHistory::default(),
+    }
+}
+
+fn save_history(path: &Path, history: &UsageHistory) -> Result<(), std::io::Error> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let data = serde_json::to_string_pretty(history).map_err(std::io::Error::other)?;
+    let tmp = path.with_extension("json.tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path)
+}
+
+/// Day of an ISO 8601 event timestamp, or today when it can't be read.
+fn event_date(timestamp: &str) -> String {
+    match timestamp.get(..10) {
+        Some(date) if is_iso_date(date) => date.to_string(),
+        _ => date_from_days(today_days()),
+    }
+}
+
+fn is_iso_date(s: &str) -> bool {
+    s.char_indices().all(|(i, c)| match i {
+        4 | 7 => c == '-',
+        _ => c.is_ascii_digit(),
+    })
+}
+
+fn today_days() -> i64 {
+    let secs = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    (secs / 86_400) as i64
+}
+
+fn current_month() -> String {
+    date_from_days(today_days())[..7].to_string()
+}
+
+/// `YYYY-MM-DD` for a number of days since 1970-01-01 (proleptic Gregorian calendar).
+fn date_from_days(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::session::events::parse_event_line;
+
+    fn event(kind: &str, timestamp: &str, data: &str) -> EventEnvelope {
+        parse_event_line(&format!(
+            r#"{{"type":"{kind}","id":"x","timestamp":"{timestamp}","data":{data}}}"#
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_date_from_days() {
+        assert_eq!(date_from_days(0), "1970-01-01");
+        assert_eq!(date_from_days(19_723), "2024-01-01");
+        assert_eq!(date_from_days(19_782), "2024-02-29");
+    }
+
+    #[test]
+    fn test_history_aggregates_by_day_project_and_model() {
+        let mut history = UsageHistory::default();
+        let prompt = r#"{"content":"fix the build"}"#;
+        history.apply(
+            "/work/a",
+            "gpt-5",
+            &event("user.message", "2025-10-01T09:00:00Z", prompt),
+        );
+        history.apply(
+            "/work/a",
+            "gpt-5",
+            &event("assistant.message", "2025-10-01T09:00:05Z", prompt),
+        );
+        history.apply(
+            "/work/a",
+            "gpt-5",
+            &event(
+                "assistant.usage",
+                "2025-10-01T09:00:05Z",
+                r#"{"model":"gpt-5","inputTokens":1200,"outputTokens":300}"#,
+            ),
+        );
+        history.apply(
+            "/work/b",
+            "claude-sonnet-4.5",
+            &event("user.message", "2025-10-02T10:00:00Z", prompt),
+        );
+        assert!(!history.apply(
+            "/work/b",
+            "gpt-5",
+            &event("assistant.turn_start", "2025-10-02T10:00:00Z", "{}")
+        ));
+
+        let summary = history.summary("2025-10-01", "2025-10", Some(300));
+        assert_eq!(summary.month_premium_requests, 2.0);
+        assert_eq!(summary.by_day.len(), 2);
+        assert_eq!(summary.by_day[0].messages, 2);
+        assert_eq!(summary.by_day[0].input_tokens, 1200);
+        assert_eq!(summary.by_project[1].key, "/work/b");
+        assert_eq!(
+            summary
+                .by_model
+                .iter()
+                .map(|b| b.key.as_str())
+                .collect::<Vec<_>>(),
+            ["claude-sonnet-4.5", "gpt-5"]
+        );
+        assert_eq!(
+            history.summary("2025-10-02", "2025-10", None).by_day.len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_premium_requests_use_model_multiplier() {
+        let mut history = UsageHistory::default();
+        let prompt = r#"{"content":"fix the build"}"#;
+        for model in ["gpt-5-mini", "claude-opus-4.5", "gpt-5", "some-new-model"] {
+            history.apply(
+                "/work",
+                model,
+                &event("user.message", "2025-10-01T09:00:00Z", prompt),
+            );
+        }
+
+        let premium = |model: &str| {
+            history
+                .records
+                .iter()
+                .find(|r| r.model == model)
+                .map(|r| r.premium_requests)
+        };
+        let opus = f64::from(models::describe("claude-opus-4.5").premium_multiplier.unwrap());
+        assert!(opus > 1.0);
+        assert_eq!(premium("gpt-5-mini"), Some(0.0));
+        assert_eq!(premium("claude-opus-4.5"), Some(opus));
+        assert_eq!(premium("gpt-5"), Some(1.0));
+        assert_eq!(premium("some-new-model"), Some(1.0));
+        assert_eq!(history.premium_requests_in_month("2025-10"), opus + 2.0);
+    }
+
+    #[test]
+    fn test_session_usage_from_events() {
+        let events = vec![
+            event("user.message", "t", r#"{"content":"hi"}"#),
+            event("session.model_change", "t", r#"{"newModel":"gpt-5"}"#),
+            event(
+                "assistant.usage",
+                "t",
+                r#"{"inputTokens":10,"outputTokens":5}"#,
+            ),
+            event("assistant.message", "t", r#"{"content":"hello"}"#),
+        ];
+        assert_eq!(
+            SessionUsage::from_events(&events),
+            SessionUsage {
+                messages: 2,
+                tokens: Some(15),
+                model: Some("gpt-5".to_string())
+            }
+        );
+    }
+
+    #[test]
+    fn test_quota_warning_sent_once_per_month() {
+        let tracker = UsageTracker::with_store(None);
+        let settings = UsageSettings {
+            premium_request_limit: Some(4),
+            warn_at_percent: 50,
+        };
+        let now = format!("{}T12:00:00Z", date_from_days(today_days()));
+        let prompt = event("user.message", &now, r#"{"content":"go"}"#);
+
+        assert!(tracker
+            .record("s", "/work", None, &prompt, &settings)
+            .is_none());
+        let warning = tracker
+            .record("s", "/work", None, &prompt, &settings)
+            .unwrap();
+        assert_eq!((warning.used, warning.limit), (2.0, 4));
+        assert!(tracker
+            .record("s", "/work", None, &prompt, &settings)
+            .is_none());
+        assert_eq!(tracker.premium_requests_this_month(), 3.0);
+    }
+}
@@ -9,11 +9,13 @@ import { useAuthStore } from '@/stores/authStore';
 import { useSessionStore } from '@/stores/sessionStore';
 import { useKeyboard } from '@/hooks/useKeyboard';
 import { tauriApi } from '@/lib/tauri';
+import { listen } from '@tauri-apps/api/event';
+import type { AuthStatus } from '@/types';
 import { useEffect, useState, useMemo } from 'react';
 
 export default function App() {
   const { setCopilotStatus } = useSettingsStore();
-  const { status, isCheckingAuth, checkAuth } = useAuthStore();
+  const { status, isCheckingAuth, checkAuth, setStatus, loadAccounts } = useAuthStore();
   const activeSessionId = useSessionStore((s) => s.activeSessionId);
   const addSession = useSessionStore((s) => s.addSession);
   const [paletteOpen, setPaletteOpen] = useState(false);
@@ -37,6 +39,9 @@ export default function App() {
             mode: 'suggest' as const,
             created_at: Date.now(),
             is_active: true,
+            copilot_session_id: null,
+            crash: null,
+            account: null,
           }),
       },
     ],
@@ -50,6 +55,17 @@ export default function App() {
     checkAuth();
   }, [setCopilotStatus, checkAuth]);
 
+  // Logins, logouts and account switches made in a terminal outside the app
+  useEffect(() => {
+    const unlisten = listen<AuthStatus>('auth-changed', (event) => {
+      setStatus(event.payload);
+      loadAccounts();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [setStatus, loadAccounts]);
+
   if (isCheckingAuth || !status) {
     return (
       <div className="h-screen w-screen flex items-center justify-center bg-[#0e0e0e]">
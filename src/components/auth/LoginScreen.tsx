@@ -1,38 +1,57 @@
-import { useState } from 'react';
+import { useState, useEffect } from 'react';
+import { listen } from '@tauri-apps/api/event';
 import { cn } from '@/lib/utils';
 import { useAuthStore } from '@/stores/authStore';
 import { tauriApi } from '@/lib/tauri';
+import type { LoginChallenge, LoginEvent } from '@/types';
 
 export function LoginScreen() {
   const { checkAuth } = useAuthStore();
   const [isLoading, setIsLoading] = useState(false);
   const [statusText, setStatusText] = useState('');
+  const [challenge, setChallenge] = useState<LoginChallenge | null>(null);
+
+  useEffect(() => {
+    let cancelled = false;
+    const unlisten = listen<LoginEvent>('login-event', async (event) => {
+      if (cancelled) return;
+      const payload = event.payload;
+      switch (payload.type) {
+        case 'Challenge':
+          setChallenge(payload.data);
+          setStatusText('Waiting for GitHub authorization...');
+          break;
+        case 'Succeeded':
+          setIsLoading(false);
+          setChallenge(null);
+          setStatusText('');
+          await checkAuth();
+          break;
+        case 'Failed':
+          setIsLoading(false);
+          setChallenge(null);
+          setStatusText(`Sign-in failed: ${payload.data}`);
+          break;
+        case 'TimedOut':
+          setIsLoading(false);
+          setChallenge(null);
+          setStatusText('Authorization timed out. Please try again.');
+          break;
+      }
+    });
+    return () => {
+      cancelled = true;
+      unlisten.then((fn) => fn());
+    };
+  }, [checkAuth]);
 
   const handleLogin = async () => {
     setIsLoading(true);
-    setStatusText('Waiting for GitHub authorization...');
+    setChallenge(null);
+    setStatusText('Starting sign-in...');
 
     try {
-      // Trigger the login flow — the copilot CLI will open a browser
       await tauriApi.triggerLogin();
-
-      // Poll for auth status
-      const pollInterval = setInterval(async () => {
-        const status = await tauriApi.checkAuth();
-        if (status.authenticated) {
-          clearInterval(pollInterval);
-          setIsLoading(false);
-          setStatusText('');
-          await checkAuth();
-        }
-      }, 2000);
-
-      // Timeout after 2 minutes
-      setTimeout(() => {
-        clearInterval(pollInterval);
-        setIsLoading(false);
-        setStatusText('Authorization timed out. Please try again.');
-      }, 120_000);
     } catch (err) {
       console.error('Login error:', err);
       setIsLoading(false);
@@ -115,6 +134,28 @@ export function LoginScreen() {
           {isLoading ? 'Signing in...' : 'Sign in with GitHub'}
         </button>
 
+        {/* Device Code */}
+        {challenge && (
+          <div className="w-full flex flex-col items-center gap-2 p-4 rounded-xl bg-zinc-800/50 border border-zinc-700/40">
+            <span className="text-xs text-zinc-400">Enter this code at</span>
+            <a
+              href={challenge.verification_uri}
+              target="_blank"
+              rel="noreferrer"
+              className="text-xs text-blue-400 hover:underline"
+            >
+              {challenge.verification_uri}
+            </a>
+            <button
+              onClick={() => navigator.clipboard.writeText(challenge.user_code)}
+              className="font-mono text-2xl tracking-widest text-zinc-100 hover:text-white"
+              title="Copy code"
+            >
+              {challenge.user_code}
+            </button>
+          </div>
+        )}
+
         {/* Status Text */}
         {statusText && (
           <p className="text-xs text-zinc-400 text-center animate-pulse">
@@ -48,7 +48,7 @@ export function ChatPanel() {
   }, [inputValue, isStreaming, setInputValue, sendPrompt]);
 
   if (!copilotStatus?.installed) {
-    return <CopilotNotFound />;
+    return <CopilotNotFound error={copilotStatus?.error ?? null} />;
   }
 
   if (!activeSessionId) {
@@ -142,7 +142,7 @@ function WelcomeScreen() {
   );
 }
 
-function CopilotNotFound() {
+function CopilotNotFound({ error }: { error: string | null }) {
   return (
     <div className="flex-1 flex items-center justify-center">
       <div className="text-center space-y-4 max-w-md">
@@ -152,6 +152,11 @@ function CopilotNotFound() {
         <h2 className="text-xl font-semibold text-neutral-200">
           Copilot CLI Not Found
         </h2>
+        {error && (
+          <p className="text-amber-400 text-sm">
+            {error}. Fix or clear the CLI path in Settings.
+          </p>
+        )}
         <p className="text-neutral-500 text-sm">
           Please install GitHub Copilot CLI first:
         </p>
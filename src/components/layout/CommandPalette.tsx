@@ -53,6 +53,9 @@ export function CommandPalette({ open, onClose }: CommandPaletteProps) {
             mode: 'suggest',
             created_at: Date.now(),
             is_active: true,
+            copilot_session_id: null,
+            crash: null,
+            account: null,
           });
           onClose();
         },
@@ -3,7 +3,7 @@ import { open } from '@tauri-apps/plugin-dialog';
 import { cn } from '@/lib/utils';
 import { tauriApi } from '@/lib/tauri';
 import { useSessionStore } from '@/stores/sessionStore';
-import type { CopilotSession } from '@/types';
+import type { CopilotSession, SessionInfo } from '@/types';
 
 interface ProjectPickerProps {
   onProjectOpened?: () => void;
@@ -26,12 +26,14 @@ function timeAgo(dateStr: string): string {
 export function ProjectPicker({ onProjectOpened }: ProjectPickerProps) {
   const [recentProjects, setRecentProjects] = useState<string[]>([]);
   const [copilotSessions, setCopilotSessions] = useState<CopilotSession[]>([]);
+  const [restorableSessions, setRestorableSessions] = useState<SessionInfo[]>([]);
   const [isOpening, setIsOpening] = useState(false);
   const { addSession } = useSessionStore();
 
   useEffect(() => {
     tauriApi.getRecentProjects().then(setRecentProjects).catch(console.error);
     tauriApi.listCopilotSessions().then(setCopilotSessions).catch(console.error);
+    tauriApi.listRestorableSessions().then(setRestorableSessions).catch(console.error);
   }, []);
 
   const openProject = useCallback(
@@ -68,6 +70,22 @@ export function ProjectPicker({ onProjectOpened }: ProjectPickerProps) {
     [addSession, onProjectOpened],
   );
 
+  const restoreSession = useCallback(
+    async (sessionId: string) => {
+      setIsOpening(true);
+      try {
+        const session = await tauriApi.restoreSession(sessionId);
+        addSession(session);
+        onProjectOpened?.();
+      } catch (err) {
+        console.error('Failed to restore session:', err);
+      } finally {
+        setIsOpening(false);
+      }
+    },
+    [addSession, onProjectOpened],
+  );
+
   const handleSelectFolder = useCallback(async () => {
     try {
       const selected = await open({ directory: true, multiple: false });
@@ -129,6 +147,36 @@ export function ProjectPicker({ onProjectOpened }: ProjectPickerProps) {
           </span>
         </button>
 
+        {/* Desktop sessions from the last run */}
+        {restorableSessions.length > 0 && (
+          <div className="flex flex-col gap-2">
+            <h3 className="text-xs font-medium text-zinc-500 uppercase tracking-wider">
+              Restore Sessions
+            </h3>
+            <div className="flex flex-col gap-1 max-h-40 overflow-y-auto">
+              {restorableSessions.map((session) => (
+                <button
+                  key={session.id}
+                  onClick={() => restoreSession(session.id)}
+                  disabled={isOpening}
+                  className={cn(
+                    'flex flex-col gap-0.5 px-4 py-3 rounded-lg text-left',
+                    'hover:bg-zinc-800/60 transition-colors duration-150',
+                    'disabled:opacity-50 disabled:cursor-not-allowed',
+                  )}
+                >
+                  <span className="text-sm font-medium text-zinc-200 truncate">
+                    {session.name}
+                  </span>
+                  <span className="text-xs text-zinc-500 truncate">
+                    {session.working_dir}
+                  </span>
+                </button>
+              ))}
+            </div>
+          </div>
+        )}
+
         {/* Recent Copilot Sessions */}
         {copilotSessions.length > 0 && (
           <div className="flex flex-col gap-2">
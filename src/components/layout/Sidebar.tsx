@@ -83,6 +83,9 @@ export function Sidebar() {
       mode: 'suggest' as const,
       created_at: new Date(cs.created_at).getTime(),
       is_active: true,
+      copilot_session_id: cs.id,
+      crash: null,
+      account: null,
     });
     setActiveSession(sessionId);
     try {
@@ -112,6 +115,9 @@ export function Sidebar() {
       mode: 'suggest' as const,
       created_at: Date.now(),
       is_active: true,
+      copilot_session_id: null,
+      crash: null,
+      account: null,
     });
   }, [addSession]);
 
@@ -0,0 +1,198 @@
+import { useEffect, useState } from 'react';
+import { cn } from '@/lib/utils';
+import { tauriApi } from '@/lib/tauri';
+import type { McpImportCandidate, McpImportSource, McpScope } from '@/types';
+
+interface McpImportDialogProps {
+  /** Working directory whose project configs are searched and can receive the servers */
+  workingDir: string | null;
+  onImported: () => void;
+  onCancel: () => void;
+}
+
+const SOURCE_LABELS: Record<McpImportSource, string> = {
+  vscode: 'VS Code',
+  'claude-desktop': 'Claude Desktop',
+  cursor: 'Cursor',
+};
+
+const candidateKey = (c: McpImportCandidate) => `${c.path}#${c.server.name}`;
+
+export function McpImportDialog({ workingDir, onImported, onCancel }: McpImportDialogProps) {
+  const [candidates, setCandidates] = useState<McpImportCandidate[] | null>(null);
+  const [selected, setSelected] = useState<Set<string>>(new Set());
+  const [scope, setScope] = useState<McpScope>('global');
+  const [replace, setReplace] = useState(false);
+  const [importing, setImporting] = useState(false);
+  const [error, setError] = useState<string | null>(null);
+
+  useEffect(() => {
+    tauriApi
+      .previewMcpImport(workingDir)
+      .then((found) => {
+        setCandidates(found);
+        // Start with the first server of each new name
+        const names = new Set<string>();
+        const initial = new Set<string>();
+        for (const c of found) {
+          if (!c.conflict && !names.has(c.server.name)) {
+            names.add(c.server.name);
+            initial.add(candidateKey(c));
+          }
+        }
+        setSelected(initial);
+      })
+      .catch((err) => setError(String(err)));
+  }, [workingDir]);
+
+  const chosen = (candidates ?? []).filter((c) => selected.has(candidateKey(c)));
+  const names = chosen.map((c) => c.server.name);
+  const duplicate = names.find((name, i) => names.indexOf(name) !== i);
+  const conflicts = chosen.filter((c) => c.conflict).length;
+
+  const toggle = (candidate: McpImportCandidate) => {
+    const key = candidateKey(candidate);
+    setSelected((prev) => {
+      const next = new Set(prev);
+      if (next.has(key)) next.delete(key);
+      else next.add(key);
+      return next;
+    });
+  };
+
+  const handleImport = async () => {
+    setImporting(true);
+    setError(null);
+    try {
+      await tauriApi.importMcpServers(
+        chosen.map((c) => ({ ...c.server, scope })),
+        scope,
+        replace,
+        workingDir
+      );
+      onImported();
+    } catch (err) {
+      setError(String(err));
+    } finally {
+      setImporting(false);
+    }
+  };
+
+  const inputClass = cn(
+    'w-full px-3 py-2 rounded-lg text-sm',
+    'bg-zinc-800/50 border border-zinc-700/30 text-zinc-200',
+    'focus:outline-none focus:border-blue-500/50'
+  );
+
+  return (
+    <div className="fixed inset-0 z-50 flex items-center justify-center bg-black/50 backdrop-blur-sm">
+      <div className="w-full max-w-2xl mx-4 rounded-xl bg-zinc-900 border border-zinc-700/50 shadow-2xl">
+        <div className="p-6 border-b border-zinc-800">
+          <h2 className="text-lg font-semibold text-zinc-100">Import MCP Servers</h2>
+          <p className="text-sm text-zinc-500 mt-1">
+            Servers configured in VS Code, Claude Desktop and Cursor
+          </p>
+        </div>
+
+        <div className="p-6 space-y-4 max-h-[60vh] overflow-y-auto">
+          {candidates === null ? (
+            !error && <p className="text-sm text-zinc-500">Looking for configs...</p>
+          ) : candidates.length === 0 ? (
+            <p className="text-sm text-zinc-500">No MCP servers found in other tools' configs.</p>
+          ) : (
+            <div className="space-y-2">
+              {candidates.map((c) => (
+                <label
+                  key={candidateKey(c)}
+                  className="flex items-start gap-3 p-3 rounded-lg bg-zinc-800/40 border border-zinc-700/30 cursor-pointer"
+                >
+                  <input
+                    type="checkbox"
+                    checked={selected.has(candidateKey(c))}
+                    onChange={() => toggle(c)}
+                    className="mt-1"
+                  />
+                  <div className="min-w-0 flex-1">
+                    <div className="flex items-center gap-2">
+                      <span className="text-sm font-medium text-zinc-200">{c.server.name}</span>
+                      <span className="px-1.5 py-0.5 rounded text-[10px] bg-zinc-700/50 text-zinc-400">
+                        {SOURCE_LABELS[c.source]}
+                      </span>
+                      {c.conflict && (
+                        <span className="px-1.5 py-0.5 rounded text-[10px] bg-amber-500/15 text-amber-400">
+                          exists ({c.conflict})
+                        </span>
+                      )}
+                    </div>
+                    <p className="text-xs text-zinc-500 font-mono truncate mt-0.5">
+                      {c.server.transport === 'local'
+                        ? [c.server.command, ...c.server.args].join(' ')
+                        : c.server.url}
+                    </p>
+                    <p className="text-[11px] text-zinc-600 truncate">{c.path}</p>
+                    {c.warnings.map((w) => (
+                      <p key={w} className="text-xs text-amber-400/80 mt-0.5">
+                        {w}
+                      </p>
+                    ))}
+                  </div>
+                </label>
+              ))}
+            </div>
+          )}
+
+          {candidates && candidates.length > 0 && (
+            <div className="space-y-3 pt-2">
+              {workingDir && (
+                <div>
+                  <label className="block text-sm font-medium text-zinc-400 mb-1">Import into</label>
+                  <select
+                    value={scope}
+                    onChange={(e) => setScope(e.target.value as McpScope)}
+                    className={inputClass}
+                  >
+                    <option value="global">Global (~/.copilot/mcp-config.json)</option>
+                    <option value="project">Project (.copilot/mcp-config.json in {workingDir})</option>
+                  </select>
+                </div>
+              )}
+              <label className="flex items-center gap-2 text-sm text-zinc-400">
+                <input
+                  type="checkbox"
+                  checked={replace}
+                  onChange={(e) => setReplace(e.target.checked)}
+                />
+                Replace existing servers with the same name
+              </label>
+            </div>
+          )}
+
+          {duplicate && (
+            <p className="text-xs text-red-400">
+              '{duplicate}' is selected more than once; pick one
+            </p>
+          )}
+          {error && <p className="text-xs text-red-400">{error}</p>}
+        </div>
+
+        <div className="p-6 border-t border-zinc-800 flex justify-end gap-3">
+          <button
+            type="button"
+            onClick={onCancel}
+            className="px-4 py-2 rounded-lg text-sm font-medium text-zinc-400 hover:text-zinc-200 transition-colors"
+          >
+            Cancel
+          </button>
+          <button
+            type="button"
+            onClick={handleImport}
+            disabled={chosen.length === 0 || !!duplicate || (conflicts > 0 && !replace) || importing}
+            className="px-4 py-2 rounded-lg text-sm font-medium bg-blue-600 hover:bg-blue-500 text-white transition-colors disabled:opacity-50"
+          >
+            {importing ? 'Importing...' : `Import ${chosen.length || ''}`.trim()}
+          </button>
+        </div>
+      </div>
+    </div>
+  );
+}
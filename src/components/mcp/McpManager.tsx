@@ -1,32 +1,52 @@
 import { useState, useCallback, useEffect } from 'react';
 import { cn } from '@/lib/utils';
 import { useMcpStore } from '@/stores/mcpStore';
+import { useSessionStore } from '@/stores/sessionStore';
 import { tauriApi } from '@/lib/tauri';
+import { listen } from '@tauri-apps/api/event';
 import { McpServerCard } from './McpServerCard';
 import { McpServerForm } from './McpServerForm';
-import type { McpServerConfig } from '@/types';
+import { McpImportDialog } from './McpImportDialog';
+import type { McpProbeResult, McpServerConfig } from '@/types';
 
 export function McpManager() {
   const { servers, isLoading, searchQuery, setServers, setLoading, setSearchQuery } = useMcpStore();
   const [showForm, setShowForm] = useState(false);
+  const [showImport, setShowImport] = useState(false);
   const [editingServer, setEditingServer] = useState<McpServerConfig | null>(null);
+  const [probes, setProbes] = useState<Record<string, McpProbeResult>>({});
+  const [probing, setProbing] = useState<string | null>(null);
+  // Project servers are those of the active session's working directory
+  const workingDir = useSessionStore(
+    (s) => s.sessions.find((session) => session.id === s.activeSessionId)?.working_dir ?? null
+  );
 
   const loadServers = useCallback(async () => {
     setLoading(true);
     try {
-      const list = await tauriApi.listMcpServers();
+      const list = await tauriApi.listMcpServers(workingDir);
       setServers(list);
     } catch (err) {
       console.error('Failed to load MCP servers:', err);
     } finally {
       setLoading(false);
     }
-  }, [setServers, setLoading]);
+  }, [setServers, setLoading, workingDir]);
 
   useEffect(() => {
     loadServers();
   }, [loadServers]);
 
+  useEffect(() => {
+    // The payload only has global servers; reload to layer the project's over them
+    const unlisten = listen<McpServerConfig[]>('mcp-config-changed', () => {
+      loadServers();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, [loadServers]);
+
   const handleAdd = () => {
     setEditingServer(null);
     setShowForm(true);
@@ -39,7 +59,7 @@ export function McpManager() {
 
   const handleDelete = async (name: string) => {
     try {
-      await tauriApi.deleteMcpServer(name);
+      await tauriApi.deleteMcpServer(name, workingDir);
       await loadServers();
     } catch (err) {
       console.error('Failed to delete MCP server:', err);
@@ -48,19 +68,32 @@ export function McpManager() {
 
   const handleToggle = async (name: string, enabled: boolean) => {
     try {
-      await tauriApi.toggleMcpServer(name, enabled);
+      await tauriApi.toggleMcpServer(name, enabled, workingDir);
       useMcpStore.getState().toggleServer(name);
     } catch (err) {
       console.error('Failed to toggle MCP server:', err);
     }
   };
 
+  const handleProbe = async (name: string) => {
+    setProbing(name);
+    try {
+      const result = await tauriApi.probeMcpServer(name, workingDir);
+      setProbes((prev) => ({ ...prev, [name]: result }));
+      await loadServers();
+    } catch (err) {
+      console.error('Failed to probe MCP server:', err);
+    } finally {
+      setProbing(null);
+    }
+  };
+
   const handleFormSave = async (server: McpServerConfig) => {
     try {
       if (editingServer) {
-        await tauriApi.updateMcpServer(editingServer.name, server);
+        await tauriApi.updateMcpServer(editingServer.name, server, workingDir);
       } else {
-        await tauriApi.addMcpServer(server);
+        await tauriApi.addMcpServer(server, workingDir);
       }
       setShowForm(false);
       setEditingServer(null);
@@ -79,15 +112,26 @@ export function McpManager() {
       <div className="p-6 border-b border-zinc-800">
         <div className="flex items-center justify-between mb-4">
           <h1 className="text-xl font-semibold text-zinc-100">MCP Servers</h1>
-          <button
-            onClick={handleAdd}
-            className={cn(
-              'px-4 py-2 rounded-lg text-sm font-medium transition-colors',
-              'bg-blue-600 hover:bg-blue-500 text-white'
-            )}
-          >
-            + Add Server
-          </button>
+          <div className="flex gap-2">
+            <button
+              onClick={() => setShowImport(true)}
+              className={cn(
+                'px-4 py-2 rounded-lg text-sm font-medium transition-colors',
+                'bg-zinc-800 hover:bg-zinc-700 text-zinc-200'
+              )}
+            >
+              Import
+            </button>
+            <button
+              onClick={handleAdd}
+              className={cn(
+                'px-4 py-2 rounded-lg text-sm font-medium transition-colors',
+                'bg-blue-600 hover:bg-blue-500 text-white'
+              )}
+            >
+              + Add Server
+            </button>
+          </div>
         </div>
 
         <input
@@ -127,6 +171,9 @@ export function McpManager() {
                 onEdit={handleEdit}
                 onDelete={handleDelete}
                 onToggle={handleToggle}
+                probe={probes[server.name]}
+                probing={probing === server.name}
+                onProbe={handleProbe}
               />
             ))}
           </div>
@@ -136,6 +183,7 @@ export function McpManager() {
       {showForm && (
         <McpServerForm
           server={editingServer}
+          workingDir={workingDir}
           onSave={handleFormSave}
           onCancel={() => {
             setShowForm(false);
@@ -143,6 +191,17 @@ export function McpManager() {
           }}
         />
       )}
+
+      {showImport && (
+        <McpImportDialog
+          workingDir={workingDir}
+          onImported={() => {
+            setShowImport(false);
+            loadServers();
+          }}
+          onCancel={() => setShowImport(false)}
+        />
+      )}
     </div>
   );
 }
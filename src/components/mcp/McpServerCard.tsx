@@ -1,15 +1,26 @@
 import { useState, useRef, useEffect } from 'react';
 import { cn } from '@/lib/utils';
-import type { McpServerConfig } from '@/types';
+import type { McpProbeResult, McpServerConfig } from '@/types';
 
 interface McpServerCardProps {
   server: McpServerConfig;
   onEdit: (server: McpServerConfig) => void;
   onDelete: (name: string) => void;
   onToggle: (name: string, enabled: boolean) => void;
+  probe?: McpProbeResult;
+  probing?: boolean;
+  onProbe: (name: string) => void;
 }
 
-export function McpServerCard({ server, onEdit, onDelete, onToggle }: McpServerCardProps) {
+export function McpServerCard({
+  server,
+  onEdit,
+  onDelete,
+  onToggle,
+  probe,
+  probing,
+  onProbe,
+}: McpServerCardProps) {
   const [expanded, setExpanded] = useState(false);
   const [menuOpen, setMenuOpen] = useState(false);
   const menuRef = useRef<HTMLDivElement>(null);
@@ -28,12 +39,18 @@ export function McpServerCard({ server, onEdit, onDelete, onToggle }: McpServerC
     running: 'bg-green-500/20 text-green-400',
     stopped: 'bg-zinc-600/20 text-zinc-400',
     error: 'bg-red-500/20 text-red-400',
+    healthy: 'bg-green-500/20 text-green-400',
+    failing: 'bg-red-500/20 text-red-400',
+    checking: 'bg-blue-500/20 text-blue-400',
   };
 
-  const statusLabel = server.status ?? (server.enabled ? 'stopped' : 'stopped');
+  const statusLabel = probing
+    ? 'checking'
+    : server.status ?? (server.enabled ? 'stopped' : 'stopped');
   const colorClass = statusColor[statusLabel as keyof typeof statusColor] ?? statusColor.stopped;
 
   const envEntries = server.env ? Object.entries(server.env) : [];
+  const headerEntries = server.headers ? Object.entries(server.headers) : [];
 
   return (
     <div
@@ -50,8 +67,26 @@ export function McpServerCard({ server, onEdit, onDelete, onToggle }: McpServerC
             <span className={cn('px-2 py-0.5 rounded-full text-xs font-medium', colorClass)}>
               {statusLabel}
             </span>
+            {server.scope === 'project' && (
+              <span
+                className="px-2 py-0.5 rounded-full text-xs font-medium bg-purple-500/20 text-purple-300"
+                title="From .copilot/mcp-config.json in the project; overrides a global server of the same name"
+              >
+                project
+              </span>
+            )}
           </div>
-          <p className="text-sm font-mono text-zinc-400 truncate">{server.command}</p>
+          <p className="text-sm font-mono text-zinc-400 truncate">
+            {server.transport === 'local' ? server.command : server.url}
+            {server.transport !== 'local' && (
+              <span className="ml-2 text-xs uppercase text-zinc-500">{server.transport}</span>
+            )}
+          </p>
+          {server.warnings.map((warning) => (
+            <p key={warning} className="text-xs text-amber-400/80 mt-1">
+              {warning}
+            </p>
+          ))}
         </div>
 
         <div className="flex items-center gap-2 shrink-0">
@@ -93,6 +128,15 @@ export function McpServerCard({ server, onEdit, onDelete, onToggle }: McpServerC
                 >
                   Edit
                 </button>
+                {server.transport === 'local' && (
+                  <button
+                    onClick={() => { onProbe(server.name); setExpanded(true); setMenuOpen(false); }}
+                    disabled={probing}
+                    className="w-full text-left px-3 py-1.5 text-sm text-zinc-200 hover:bg-zinc-700/50 disabled:opacity-50"
+                  >
+                    Test connection
+                  </button>
+                )}
                 <button
                   onClick={() => { onDelete(server.name); setMenuOpen(false); }}
                   className="w-full text-left px-3 py-1.5 text-sm text-red-400 hover:bg-zinc-700/50"
@@ -135,6 +179,56 @@ export function McpServerCard({ server, onEdit, onDelete, onToggle }: McpServerC
               </div>
             </div>
           )}
+
+          {probe && (
+            <div>
+              <p className="text-xs font-medium text-zinc-500 mb-1">
+                Last check
+                {probe.server_info && <span className="text-zinc-400"> · {probe.server_info}</span>}
+                {probe.startup_ms !== null && (
+                  <span className="text-zinc-400"> · started in {probe.startup_ms} ms</span>
+                )}
+              </p>
+              {probe.error && <p className="text-xs text-red-400 mb-1">{probe.error}</p>}
+              {probe.health === 'healthy' && (
+                <p className="text-xs text-zinc-400 mb-1">
+                  {probe.tools.length} tools · {probe.resources.length} resources ·{' '}
+                  {probe.prompts.length} prompts
+                </p>
+              )}
+              {probe.tools.length > 0 && (
+                <div className="flex flex-wrap gap-1">
+                  {probe.tools.map((tool) => (
+                    <span
+                      key={tool.name}
+                      title={tool.description ?? undefined}
+                      className="px-2 py-0.5 rounded bg-zinc-700/50 text-xs font-mono text-zinc-300"
+                    >
+                      {tool.name}
+                    </span>
+                  ))}
+                </div>
+              )}
+              {probe.health === 'failing' && probe.stderr_tail.length > 0 && (
+                <pre className="mt-1 p-2 rounded bg-zinc-900/60 text-xs font-mono text-zinc-400 whitespace-pre-wrap max-h-40 overflow-y-auto">
+                  {probe.stderr_tail.join('\n')}
+                </pre>
+              )}
+            </div>
+          )}
+
+          {headerEntries.length > 0 && (
+            <div>
+              <p className="text-xs font-medium text-zinc-500 mb-1">Headers</p>
+              <div className="space-y-0.5">
+                {headerEntries.map(([key, value]) => (
+                  <p key={key} className="text-xs font-mono text-zinc-400">
+                    <span className="text-zinc-300">{key}</span>: {value}
+                  </p>
+                ))}
+              </div>
+            </div>
+          )}
         </div>
       )}
     </div>
@@ -1,15 +1,42 @@
 import { useState } from 'react';
 import { cn } from '@/lib/utils';
-import type { McpServerConfig } from '@/types';
+import type { McpScope, McpServerConfig, McpTransport } from '@/types';
 
 interface McpServerFormProps {
   server: McpServerConfig | null;
+  /** Working directory whose project config can hold the server */
+  workingDir: string | null;
   onSave: (server: McpServerConfig) => void;
   onCancel: () => void;
 }
 
-export function McpServerForm({ server, onSave, onCancel }: McpServerFormProps) {
+function parseLines(text: string, separator: string): Record<string, string> | null {
+  const values: Record<string, string> = {};
+  text
+    .split('\n')
+    .map((l) => l.trim())
+    .filter(Boolean)
+    .forEach((line) => {
+      const idx = line.indexOf(separator);
+      if (idx > 0) {
+        values[line.slice(0, idx).trim()] = line.slice(idx + 1).trim();
+      }
+    });
+  return Object.keys(values).length > 0 ? values : null;
+}
+
+export function McpServerForm({ server, workingDir, onSave, onCancel }: McpServerFormProps) {
   const [name, setName] = useState(server?.name ?? '');
+  const [scope, setScope] = useState<McpScope>(server?.scope ?? 'global');
+  const [transport, setTransport] = useState<McpTransport>(server?.transport ?? 'local');
+  const [url, setUrl] = useState(server?.url ?? '');
+  const [headersText, setHeadersText] = useState(
+    server?.headers
+      ? Object.entries(server.headers)
+          .map(([k, v]) => `${k}: ${v}`)
+          .join('\n')
+      : ''
+  );
   const [command, setCommand] = useState(server?.command ?? '');
   const [argsText, setArgsText] = useState(server?.args.join('\n') ?? '');
   const [envText, setEnvText] = useState(
@@ -24,7 +51,11 @@ export function McpServerForm({ server, onSave, onCancel }: McpServerFormProps)
   const validate = (): boolean => {
     const errs: Record<string, string> = {};
     if (!name.trim()) errs.name = 'Name is required';
-    if (!command.trim()) errs.command = 'Command is required';
+    if (transport === 'local') {
+      if (!command.trim()) errs.command = 'Command is required';
+    } else if (!/^https?:\/\/[^\s/]+/.test(url.trim())) {
+      errs.url = 'A URL starting with http:// or https:// is required';
+    }
     setErrors(errs);
     return Object.keys(errs).length === 0;
   };
@@ -38,25 +69,19 @@ export function McpServerForm({ server, onSave, onCancel }: McpServerFormProps)
       .map((a) => a.trim())
       .filter(Boolean);
 
-    const env: Record<string, string> = {};
-    envText
-      .split('\n')
-      .map((l) => l.trim())
-      .filter(Boolean)
-      .forEach((line) => {
-        const idx = line.indexOf('=');
-        if (idx > 0) {
-          env[line.slice(0, idx)] = line.slice(idx + 1);
-        }
-      });
-
+    const local = transport === 'local';
     onSave({
       name: name.trim(),
-      command: command.trim(),
-      args,
-      env: Object.keys(env).length > 0 ? env : null,
+      scope,
+      transport,
+      command: local ? command.trim() : '',
+      args: local ? args : [],
+      env: local ? parseLines(envText, '=') : null,
+      url: local ? null : url.trim(),
+      headers: local ? null : parseLines(headersText, ':'),
       enabled: server?.enabled ?? true,
       status: null,
+      warnings: [],
     });
   };
 
@@ -91,43 +116,111 @@ export function McpServerForm({ server, onSave, onCancel }: McpServerFormProps)
             {errors.name && <p className="text-xs text-red-400 mt-1">{errors.name}</p>}
           </div>
 
-          <div>
-            <label className="block text-sm font-medium text-zinc-400 mb-1">Command</label>
-            <input
-              type="text"
-              value={command}
-              onChange={(e) => setCommand(e.target.value)}
-              placeholder="npx or /path/to/server"
-              className={cn(inputClass, errors.command && 'border-red-500/50')}
-            />
-            {errors.command && <p className="text-xs text-red-400 mt-1">{errors.command}</p>}
-          </div>
+          {workingDir && (
+            <div>
+              <label className="block text-sm font-medium text-zinc-400 mb-1">Scope</label>
+              <select
+                value={scope}
+                onChange={(e) => setScope(e.target.value as McpScope)}
+                disabled={server !== null}
+                className={cn(inputClass, 'disabled:opacity-60')}
+              >
+                <option value="global">Global (~/.copilot/mcp-config.json)</option>
+                <option value="project">Project (.copilot/mcp-config.json in {workingDir})</option>
+              </select>
+            </div>
+          )}
 
           <div>
-            <label className="block text-sm font-medium text-zinc-400 mb-1">
-              Arguments <span className="text-zinc-600">(one per line)</span>
-            </label>
-            <textarea
-              value={argsText}
-              onChange={(e) => setArgsText(e.target.value)}
-              placeholder={'-y\n@modelcontextprotocol/server-filesystem'}
-              rows={3}
-              className={cn(inputClass, 'font-mono resize-none')}
-            />
+            <label className="block text-sm font-medium text-zinc-400 mb-1">Transport</label>
+            <select
+              value={transport}
+              onChange={(e) => setTransport(e.target.value as McpTransport)}
+              className={inputClass}
+            >
+              <option value="local">Local (stdio)</option>
+              <option value="http">HTTP</option>
+              <option value="sse">SSE</option>
+            </select>
           </div>
 
-          <div>
-            <label className="block text-sm font-medium text-zinc-400 mb-1">
-              Environment Variables <span className="text-zinc-600">(KEY=value per line)</span>
-            </label>
-            <textarea
-              value={envText}
-              onChange={(e) => setEnvText(e.target.value)}
-              placeholder="API_KEY=xxx"
-              rows={2}
-              className={cn(inputClass, 'font-mono resize-none')}
-            />
-          </div>
+          {transport === 'local' ? (
+            <>
+              <div>
+                <label className="block text-sm font-medium text-zinc-400 mb-1">Command</label>
+                <input
+                  type="text"
+                  value={command}
+                  onChange={(e) => setCommand(e.target.value)}
+                  placeholder="npx or /path/to/server"
+                  className={cn(inputClass, errors.command && 'border-red-500/50')}
+                />
+                {errors.command && <p className="text-xs text-red-400 mt-1">{errors.command}</p>}
+              </div>
+
+              <div>
+                <label className="block text-sm font-medium text-zinc-400 mb-1">
+                  Arguments <span className="text-zinc-600">(one per line)</span>
+                </label>
+                <textarea
+                  value={argsText}
+                  onChange={(e) => setArgsText(e.target.value)}
+                  placeholder={'-y\n@modelcontextprotocol/server-filesystem'}
+                  rows={3}
+                  className={cn(inputClass, 'font-mono resize-none')}
+                />
+              </div>
+
+              <div>
+                <label className="block text-sm font-medium text-zinc-400 mb-1">
+                  Environment Variables <span className="text-zinc-600">(KEY=value per line)</span>
+                </label>
+                <textarea
+                  value={envText}
+                  onChange={(e) => setEnvText(e.target.value)}
+                  placeholder={'API_KEY=${env:API_KEY}\nTOKEN=${file:~/.config/token}'}
+                  rows={2}
+                  className={cn(inputClass, 'font-mono resize-none')}
+                />
+                <p className="text-xs text-zinc-500 mt-1">
+                  Use <code>{'${env:NAME}'}</code> or <code>{'${file:path}'}</code> to resolve
+                  secrets when a session starts. Leave <code>********</code> to keep a stored value.
+                </p>
+              </div>
+            </>
+          ) : (
+            <>
+              <div>
+                <label className="block text-sm font-medium text-zinc-400 mb-1">URL</label>
+                <input
+                  type="text"
+                  value={url}
+                  onChange={(e) => setUrl(e.target.value)}
+                  placeholder="https://api.example.com/mcp"
+                  className={cn(inputClass, errors.url && 'border-red-500/50')}
+                />
+                {errors.url && <p className="text-xs text-red-400 mt-1">{errors.url}</p>}
+              </div>
+
+              <div>
+                <label className="block text-sm font-medium text-zinc-400 mb-1">
+                  Headers <span className="text-zinc-600">(Name: value per line)</span>
+                </label>
+                <textarea
+                  value={headersText}
+                  onChange={(e) => setHeadersText(e.target.value)}
+                  placeholder="Authorization: Bearer ${GITHUB_TOKEN}"
+                  rows={2}
+                  className={cn(inputClass, 'font-mono resize-none')}
+                />
+                <p className="text-xs text-zinc-500 mt-1">
+                  Reference tokens as <code>{'${env:NAME}'}</code> or <code>{'${file:path}'}</code>{' '}
+                  instead of storing them in the config. Leave <code>********</code> to keep a
+                  stored value.
+                </p>
+              </div>
+            </>
+          )}
         </div>
 
         <div className="p-6 border-t border-zinc-800 flex justify-end gap-3">
@@ -6,8 +6,6 @@ import { PluginCard } from './PluginCard';
 import { PluginDetails } from './PluginDetails';
 import type { PluginInfo } from '@/types';
 
-const CATEGORIES = ['All', 'Tools', 'Languages', 'Integrations'] as const;
-
 export function PluginMarketplace() {
   const {
     installed,
@@ -25,20 +23,26 @@ export function PluginMarketplace() {
   const [selectedCategory, setSelectedCategory] = useState<string>('All');
   const [selectedPlugin, setSelectedPlugin] = useState<PluginInfo | null>(null);
 
+  const [registryError, setRegistryError] = useState<string | null>(null);
+
   const fetchPlugins = useCallback(async () => {
     setLoading(true);
-    try {
-      const [installedPlugins, availablePlugins] = await Promise.all([
-        tauriApi.listInstalledPlugins(),
-        tauriApi.listAvailablePlugins(),
-      ]);
-      setInstalled(installedPlugins);
-      setAvailable(availablePlugins);
-    } catch {
-      // Silently handle — data will remain empty
-    } finally {
-      setLoading(false);
+    // Installed plugins come from disk, so list them even when the registry is unreachable
+    const [installedResult, availableResult] = await Promise.allSettled([
+      tauriApi.listInstalledPlugins(),
+      tauriApi.listAvailablePlugins(),
+    ]);
+    if (installedResult.status === 'fulfilled') {
+      setInstalled(installedResult.value);
+    }
+    if (availableResult.status === 'fulfilled') {
+      setAvailable(availableResult.value);
+      setRegistryError(null);
+    } else {
+      setAvailable([]);
+      setRegistryError(String(availableResult.reason));
     }
+    setLoading(false);
   }, [setInstalled, setAvailable, setLoading]);
 
   useEffect(() => {
@@ -46,6 +50,11 @@ export function PluginMarketplace() {
   }, [fetchPlugins]);
 
   const plugins = activeTab === 'installed' ? installed : available;
+  // Categories are whatever the registry and installed manifests use
+  const categories = [
+    'All',
+    ...Array.from(new Set(plugins.map((p) => p.category).filter((c): c is string => !!c))).sort(),
+  ];
 
   const filtered = plugins.filter((p) => {
     const matchesSearch =
@@ -120,7 +129,7 @@ export function PluginMarketplace() {
 
         {/* Category filter chips */}
         <div className="flex gap-2 flex-wrap">
-          {CATEGORIES.map((cat) => (
+          {categories.map((cat) => (
             <button
               key={cat}
               onClick={() => setSelectedCategory(cat)}
@@ -167,7 +176,9 @@ export function PluginMarketplace() {
                   d="M20 13V6a2 2 0 00-2-2H6a2 2 0 00-2 2v7m16 0v5a2 2 0 01-2 2H6a2 2 0 01-2-2v-5m16 0h-2.586a1 1 0 00-.707.293l-2.414 2.414a1 1 0 01-.707.293h-3.172a1 1 0 01-.707-.293l-2.414-2.414A1 1 0 006.586 13H4"
                 />
               </svg>
-              <p className="text-sm">No plugins found</p>
+              <p className="text-sm">
+                {activeTab === 'marketplace' && registryError ? registryError : 'No plugins found'}
+              </p>
             </div>
           ) : (
             <div className="grid grid-cols-1 lg:grid-cols-2 gap-4">
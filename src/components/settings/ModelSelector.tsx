@@ -2,8 +2,9 @@ import { useState, useEffect, useRef } from 'react';
 import { cn } from '@/lib/utils';
 import { useSessionStore } from '@/stores/sessionStore';
 import { tauriApi } from '@/lib/tauri';
+import { listen } from '@tauri-apps/api/event';
 import { ChevronDown, Check } from 'lucide-react';
-import type { ModelInfo } from '@/types';
+import type { CopilotConfigChange, ModelInfo } from '@/types';
 
 export function ModelSelector() {
   const { sessions, activeSessionId, setSessionModel } = useSessionStore();
@@ -20,6 +21,19 @@ export function ModelSelector() {
     tauriApi.getDefaultModel().then(setDefaultModel).catch(console.error);
   }, []);
 
+  useEffect(() => {
+    const unlisten = listen<CopilotConfigChange>('copilot-config-changed', (event) => {
+      if (event.payload.model) {
+        setDefaultModel(event.payload.model);
+      } else {
+        tauriApi.getDefaultModel().then(setDefaultModel).catch(console.error);
+      }
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
   useEffect(() => {
     const handler = (e: MouseEvent) => {
       if (dropdownRef.current && !dropdownRef.current.contains(e.target as Node)) {
@@ -74,13 +88,20 @@ export function ModelSelector() {
                     'flex items-center gap-2 w-full px-3 py-2 text-sm transition-colors',
                     model.id === effectiveModel
                       ? 'bg-blue-600/10 text-blue-400'
-                      : 'text-zinc-300 hover:bg-zinc-700'
+                      : 'text-zinc-300 hover:bg-zinc-700',
+                    model.available === false && 'opacity-50'
                   )}
+                  title={model.available === false ? 'Not available for this account' : undefined}
                 >
                   <div className="flex-1 min-w-0">
                     <p className="font-medium text-xs">{model.name}</p>
                     <p className="text-[10px] text-zinc-500">{model.description}</p>
                   </div>
+                  {model.premium_multiplier != null && (
+                    <span className="shrink-0 text-[10px] text-zinc-500">
+                      {model.premium_multiplier === 0 ? 'included' : `${model.premium_multiplier}x`}
+                    </span>
+                  )}
                   {model.id === effectiveModel && <Check size={14} className="shrink-0 text-blue-400" />}
                 </button>
               ))}
@@ -142,6 +142,26 @@ export function SettingsPanel() {
               checked={localConfig.notification_sound}
               onChange={(v) => updateLocal('notification_sound', v)}
             />
+
+            <Field label="Plugin Registry">
+              <input
+                type="text"
+                value={localConfig.plugin_registry ?? ''}
+                onChange={(e) => updateLocal('plugin_registry', e.target.value.trim() || null)}
+                placeholder="https://example.com/plugins/index.json or /path/to/index.json"
+                className="w-full px-3 py-2 bg-zinc-800/50 border border-zinc-700/50 rounded-lg text-sm text-zinc-200 placeholder:text-zinc-600 focus:outline-none focus:border-blue-500/50"
+              />
+            </Field>
+
+            <Field label="Plugins Directory">
+              <input
+                type="text"
+                value={localConfig.plugins_dir ?? ''}
+                onChange={(e) => updateLocal('plugins_dir', e.target.value.trim() || null)}
+                placeholder="Absolute path; defaults to ~/.copilot/plugins"
+                className="w-full px-3 py-2 bg-zinc-800/50 border border-zinc-700/50 rounded-lg text-sm text-zinc-200 placeholder:text-zinc-600 focus:outline-none focus:border-blue-500/50"
+              />
+            </Field>
           </div>
         </Section>
 
@@ -5,11 +5,11 @@ import { useSessionStore } from '@/stores/sessionStore';
 import { Activity, MessageSquare, Cpu, RefreshCw } from 'lucide-react';
 import type { UsageMetrics as UsageMetricsType } from '@/types';
 
-function ProgressRing({ used, limit, size = 100 }: { used: number; limit: number; size?: number }) {
+function ProgressRing({ used, limit, size = 100 }: { used: number; limit: number | null; size?: number }) {
   const strokeWidth = 8;
   const radius = (size - strokeWidth) / 2;
   const circumference = 2 * Math.PI * radius;
-  const pct = limit > 0 ? Math.min(used / limit, 1) : 0;
+  const pct = limit ? Math.min(used / limit, 1) : 0;
   const offset = circumference * (1 - pct);
   const color = pct > 0.9 ? '#ef4444' : pct > 0.7 ? '#f59e0b' : '#3b82f6';
 
@@ -38,8 +38,8 @@ function ProgressRing({ used, limit, size = 100 }: { used: number; limit: number
         />
       </svg>
       <div className="absolute flex flex-col items-center">
-        <span className="text-lg font-bold text-zinc-100">{used}</span>
-        <span className="text-[10px] text-zinc-500">/ {limit}</span>
+        <span className="text-lg font-bold text-zinc-100">{formatRequests(used)}</span>
+        <span className="text-[10px] text-zinc-500">/ {limit ?? '—'}</span>
       </div>
     </div>
   );
@@ -57,14 +57,7 @@ export function UsageMetrics() {
       const data = await tauriApi.getUsageMetrics(activeSessionId);
       setMetrics(data);
     } catch {
-      // Fallback metrics
-      setMetrics({
-        premium_requests_used: 0,
-        premium_requests_limit: 300,
-        session_messages: 0,
-        session_tokens: null,
-        active_model: 'claude-sonnet-4-5',
-      });
+      setMetrics(null);
     } finally {
       setLoading(false);
     }
@@ -90,12 +83,14 @@ export function UsageMetrics() {
       <div className="flex items-center gap-6 p-4 bg-zinc-800/30 rounded-lg border border-zinc-700/30">
         <ProgressRing
           used={metrics?.premium_requests_used ?? 0}
-          limit={metrics?.premium_requests_limit ?? 300}
+          limit={metrics?.premium_requests_limit ?? null}
         />
         <div>
           <h4 className="text-sm font-medium text-zinc-200">Premium Requests</h4>
           <p className="text-xs text-zinc-500 mt-0.5">
-            {metrics?.premium_requests_used ?? 0} of {metrics?.premium_requests_limit ?? 300} used
+            {metrics?.premium_requests_limit != null
+              ? `${formatRequests(metrics.premium_requests_used)} of ${metrics.premium_requests_limit} used`
+              : `${formatRequests(metrics?.premium_requests_used ?? 0)} used this month`}
           </p>
           <p className="text-[10px] text-zinc-600 mt-1">Resets monthly</p>
         </div>
@@ -168,6 +163,11 @@ function StatCard({
   );
 }
 
+/** Premium requests are fractional for models with a multiplier below 1 */
+function formatRequests(n: number): string {
+  return String(Math.round(n * 100) / 100);
+}
+
 function formatNumber(n: number): string {
   if (n >= 1_000_000) return (n / 1_000_000).toFixed(1) + 'M';
   if (n >= 1_000) return (n / 1_000).toFixed(1) + 'K';
@@ -35,7 +35,7 @@ export function useAuth() {
     if (!activeSessionId) return;
     try {
       await tauriApi.sendMessage(activeSessionId, '/logout');
-      setStatus({ authenticated: false, username: null, email: null });
+      setStatus({ authenticated: false, username: null, email: null, host: null });
     } catch (err) {
       console.error('Logout failed:', err);
     }
@@ -3,7 +3,7 @@ import { listen } from '@tauri-apps/api/event';
 import type { UnlistenFn } from '@tauri-apps/api/event';
 import { tauriApi } from '@/lib/tauri';
 import { useChatStore } from '@/stores/chatStore';
-import type { PtyEvent } from '@/types';
+import type { SequencedPtyEvent, SessionEventRecord, UsageWarning } from '@/types';
 
 export function useCopilot(sessionId: string | null) {
   const { addMessage, appendToLastMessage, setStreaming, isStreaming } = useChatStore();
@@ -49,7 +49,7 @@ export function useCopilot(sessionId: string | null) {
     let cancelled = false;
 
     const setup = async () => {
-      unlistenRef.current = await listen<PtyEvent>(
+      unlistenRef.current = await listen<SequencedPtyEvent>(
         `pty-output-${sessionId}`,
         (event) => {
           if (cancelled) return;
@@ -80,13 +80,17 @@ export function useCopilot(sessionId: string | null) {
                 appendToLastMessage(sessionId, `\n❌ ${parsed.data}\n`);
                 break;
             }
-          } else if (ptyEvent.type === 'Output') {
-            appendToLastMessage(sessionId, ptyEvent.data);
           } else if (ptyEvent.type === 'Error') {
             appendToLastMessage(sessionId, `\n\n*Error: ${ptyEvent.data}*`);
             setStreaming(false);
-          } else if (ptyEvent.type === 'Exit') {
+          } else if (ptyEvent.type === 'SessionExited') {
             setStreaming(false);
+          } else if (ptyEvent.type === 'SessionCrashed') {
+            const { report, restart_in } = ptyEvent.data;
+            const reason = report.signal ?? `exit code ${report.code ?? 'unknown'}`;
+            const restart =
+              restart_in !== null ? ` Restarting in ${Math.round(restart_in / 1000)}s…` : '';
+            appendToLastMessage(sessionId, `\n\n*Copilot crashed (${reason}).${restart}*`);
           }
         },
       );
@@ -101,5 +105,54 @@ export function useCopilot(sessionId: string | null) {
     };
   }, [sessionId, appendToLastMessage, setStreaming]);
 
+  // Structured events from the session's events.jsonl; message text already streams from the PTY
+  useEffect(() => {
+    if (!sessionId) return;
+
+    let cancelled = false;
+    let unlisten: UnlistenFn | null = null;
+    let unlistenUsage: UnlistenFn | null = null;
+
+    listen<UsageWarning>('usage-warning', (event) => {
+      if (cancelled) return;
+      const { used, limit } = event.payload;
+      const rounded = Math.round(used * 100) / 100;
+      appendToLastMessage(sessionId, `\n⚠️ ${rounded} of ${limit} premium requests used this month\n`);
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlistenUsage = fn;
+    });
+
+    listen<SessionEventRecord>(`session-event-${sessionId}`, (event) => {
+      if (cancelled) return;
+      const record = event.payload;
+      switch (record.type) {
+        case 'ToolCall':
+          appendToLastMessage(sessionId, `\n🔧 *${record.data.tool}*\n`);
+          break;
+        case 'ToolResult':
+          if (!record.data.success) {
+            appendToLastMessage(sessionId, `\n❌ ${record.data.output}\n`);
+          }
+          break;
+        case 'FileEdit':
+          appendToLastMessage(sessionId, `\n📄 *edited*: \`${record.data.path}\`\n`);
+          break;
+        case 'Error':
+          appendToLastMessage(sessionId, `\n❌ ${record.data.message}\n`);
+          break;
+      }
+    }).then((fn) => {
+      if (cancelled) fn();
+      else unlisten = fn;
+    });
+
+    return () => {
+      cancelled = true;
+      unlisten?.();
+      unlistenUsage?.();
+    };
+  }, [sessionId, appendToLastMessage]);
+
   return { sendPrompt, isStreaming };
 }
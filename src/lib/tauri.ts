@@ -1,13 +1,20 @@
 import { invoke } from '@tauri-apps/api/core';
-import type { AuthStatus, CopilotStatus, SessionInfo, AppConfig, FileChangeEvent, DiffResult, McpServerConfig, ModelInfo, AgentMode, PluginInfo, UsageMetrics, CopilotSession } from '@/types';
+import type { Account, AuthStatus, CopilotStatus, SessionInfo, AppConfig, FileChangeEvent, DiffResult, McpImportCandidate, McpProbeResult, McpScope, McpServerConfig, ModelInfo, AgentMode, PluginInfo, UsageMetrics, UsageSummary, CopilotSession, SessionOutput, KeyInput, ToolPolicy, SpawnProfile, TimelinePage } from '@/types';
 
 export const tauriApi = {
   checkCopilotStatus: () => invoke<CopilotStatus>('check_copilot_status'),
 
   checkAuth: () => invoke<AuthStatus>('check_auth'),
 
-  triggerLogin: () =>
-    invoke<void>('trigger_login'),
+  listAccounts: () => invoke<Account[]>('list_accounts'),
+
+  switchAccount: (account: Account) =>
+    invoke<AuthStatus>('switch_account', { account }),
+
+  triggerLogin: (host?: string) =>
+    invoke<void>('trigger_login', { host }),
+
+  cancelLogin: () => invoke<boolean>('cancel_login'),
 
   getDefaultModel: () => invoke<string>('get_default_model'),
 
@@ -18,12 +25,30 @@ export const tauriApi = {
 
   detectCopilotBinary: () => invoke<CopilotStatus>('detect_copilot_binary'),
 
+  setCopilotPath: (path: string | null) =>
+    invoke<CopilotStatus>('set_copilot_path', { path }),
+
   createSession: (name: string, workingDir: string) =>
     invoke<SessionInfo>('create_session', { name, workingDir }),
 
   sendMessage: (sessionId: string, message: string) =>
     invoke<void>('send_message', { sessionId, message }),
 
+  sendKeys: (sessionId: string, keys: KeyInput[]) =>
+    invoke<void>('send_keys', { sessionId, keys }),
+
+  interruptSession: (sessionId: string) =>
+    invoke<void>('interrupt_session', { sessionId }),
+
+  respondToPermission: (sessionId: string, choice: number) =>
+    invoke<void>('respond_to_permission', { sessionId, choice }),
+
+  getSessionOutput: (sessionId: string, sinceSeq?: number) =>
+    invoke<SessionOutput>('get_session_output', { sessionId, sinceSeq }),
+
+  setRawOutput: (sessionId: string, enabled: boolean) =>
+    invoke<void>('set_raw_output', { sessionId, enabled }),
+
   listSessions: () => invoke<SessionInfo[]>('list_sessions'),
 
   closeSession: (sessionId: string) =>
@@ -42,20 +67,34 @@ export const tauriApi = {
   getDiff: (path: string) =>
     invoke<DiffResult>('get_diff', { path }),
 
-  listMcpServers: () =>
-    invoke<McpServerConfig[]>('list_mcp_servers'),
+  listMcpServers: (workingDir?: string | null) =>
+    invoke<McpServerConfig[]>('list_mcp_servers', { workingDir: workingDir ?? null }),
+
+  addMcpServer: (config: McpServerConfig, workingDir?: string | null) =>
+    invoke<void>('add_mcp_server', { config, workingDir: workingDir ?? null }),
 
-  addMcpServer: (config: McpServerConfig) =>
-    invoke<void>('add_mcp_server', { config }),
+  updateMcpServer: (name: string, config: McpServerConfig, workingDir?: string | null) =>
+    invoke<void>('update_mcp_server', { name, config, workingDir: workingDir ?? null }),
 
-  updateMcpServer: (name: string, config: McpServerConfig) =>
-    invoke<void>('update_mcp_server', { name, config }),
+  deleteMcpServer: (name: string, workingDir?: string | null) =>
+    invoke<void>('delete_mcp_server', { name, workingDir: workingDir ?? null }),
 
-  deleteMcpServer: (name: string) =>
-    invoke<void>('delete_mcp_server', { name }),
+  toggleMcpServer: (name: string, enabled: boolean, workingDir?: string | null) =>
+    invoke<void>('toggle_mcp_server', { name, enabled, workingDir: workingDir ?? null }),
 
-  toggleMcpServer: (name: string, enabled: boolean) =>
-    invoke<void>('toggle_mcp_server', { name, enabled }),
+  probeMcpServer: (name: string, workingDir?: string | null) =>
+    invoke<McpProbeResult>('probe_mcp_server', { name, workingDir: workingDir ?? null }),
+
+  previewMcpImport: (workingDir?: string | null) =>
+    invoke<McpImportCandidate[]>('preview_mcp_import', { workingDir: workingDir ?? null }),
+
+  importMcpServers: (
+    servers: McpServerConfig[],
+    scope: McpScope,
+    replace: boolean,
+    workingDir?: string | null
+  ) =>
+    invoke<void>('import_mcp_servers', { servers, scope, replace, workingDir: workingDir ?? null }),
 
   renameSession: (sessionId: string, name: string) =>
     invoke<void>('rename_session', { sessionId, name }),
@@ -69,8 +108,11 @@ export const tauriApi = {
   setMode: (sessionId: string, mode: AgentMode) =>
     invoke<void>('set_mode', { sessionId, mode }),
 
-  listAvailableModels: () =>
-    invoke<ModelInfo[]>('list_available_models'),
+  setSessionAccount: (sessionId: string, account: Account | null) =>
+    invoke<void>('set_session_account', { sessionId, account }),
+
+  listAvailableModels: (refresh?: boolean) =>
+    invoke<ModelInfo[]>('list_available_models', { refresh }),
 
   sendSlashCommand: (sessionId: string, command: string) =>
     invoke<void>('send_slash_command', { sessionId, command }),
@@ -93,9 +135,21 @@ export const tauriApi = {
   updateConfig: (config: AppConfig) =>
     invoke<void>('update_config', { config }),
 
+  setProjectToolPolicy: (projectPath: string, policy: ToolPolicy | null) =>
+    invoke<void>('set_project_tool_policy', { projectPath, policy }),
+
+  setProjectSpawnProfile: (projectPath: string, profile: SpawnProfile | null) =>
+    invoke<void>('set_project_spawn_profile', { projectPath, profile }),
+
+  previewSessionArgs: (sessionId: string) =>
+    invoke<string[]>('preview_session_args', { sessionId }),
+
   getUsageMetrics: (sessionId: string) =>
     invoke<UsageMetrics>('get_usage_metrics', { sessionId }),
 
+  getUsageHistory: (days: number) =>
+    invoke<UsageSummary>('get_usage_history', { days }),
+
   clearSessionHistory: (sessionId: string) =>
     invoke<void>('clear_session_history', { sessionId }),
 
@@ -113,4 +167,13 @@ export const tauriApi = {
       'get_session_events',
       { sessionId }
     ),
+
+  getSessionTimeline: (sessionId: string, offset: number, limit: number, kinds?: string[]) =>
+    invoke<TimelinePage>('get_session_timeline', { sessionId, offset, limit, kinds: kinds ?? null }),
+
+  listRestorableSessions: () =>
+    invoke<SessionInfo[]>('list_restorable_sessions'),
+
+  restoreSession: (sessionId: string) =>
+    invoke<SessionInfo>('restore_session', { sessionId }),
 };
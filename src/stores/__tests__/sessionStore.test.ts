@@ -10,6 +10,9 @@ const makeSession = (overrides: Partial<SessionInfo> = {}): SessionInfo => ({
   mode: 'suggest',
   created_at: Date.now(),
   is_active: false,
+  copilot_session_id: null,
+  crash: null,
+  account: null,
   ...overrides,
 });
 
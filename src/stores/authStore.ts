@@ -1,16 +1,20 @@
 import { create } from 'zustand';
-import type { AuthStatus } from '@/types';
+import type { Account, AuthStatus } from '@/types';
 import { tauriApi } from '@/lib/tauri';
 
 interface AuthState {
   status: AuthStatus | null;
+  accounts: Account[];
   isCheckingAuth: boolean;
   setStatus: (status: AuthStatus) => void;
   checkAuth: () => Promise<void>;
+  loadAccounts: () => Promise<void>;
+  switchAccount: (account: Account) => Promise<void>;
 }
 
 export const useAuthStore = create<AuthState>((set) => ({
   status: null,
+  accounts: [],
   isCheckingAuth: false,
 
   setStatus: (status) => set({ status }),
@@ -23,9 +27,22 @@ export const useAuthStore = create<AuthState>((set) => ({
     } catch (err) {
       console.error('Failed to check auth:', err);
       set({
-        status: { authenticated: false, username: null, email: null },
+        status: { authenticated: false, username: null, email: null, host: null },
         isCheckingAuth: false,
       });
     }
   },
+
+  loadAccounts: async () => {
+    try {
+      set({ accounts: await tauriApi.listAccounts() });
+    } catch (err) {
+      console.error('Failed to list accounts:', err);
+    }
+  },
+
+  switchAccount: async (account) => {
+    const status = await tauriApi.switchAccount(account);
+    set({ status });
+  },
 }));
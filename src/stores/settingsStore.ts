@@ -29,6 +29,15 @@ const defaultConfig: AppConfig = {
   send_on_enter: true,
   notification_sound: false,
   accent_color: 'blue',
+  tool_policy: { allow: [], deny: [], allow_all_paths: false },
+  project_policies: {},
+  shutdown: { exit_timeout_ms: 3000, term_timeout_ms: 2000 },
+  restart: { enabled: false, max_restarts: 5, initial_backoff_ms: 1000, max_backoff_ms: 30000, stable_after_ms: 60000 },
+  spawn_profile: { env: {}, path_prepend: [], env_file: null, add_dirs: [], extra_args: [] },
+  project_profiles: {},
+  usage: { premium_request_limit: null, warn_at_percent: 80 },
+  plugin_registry: null,
+  plugins_dir: null,
 };
 
 export const useSettingsStore = create<SettingsState>((set) => ({
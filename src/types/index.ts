@@ -2,6 +2,28 @@ export interface AuthStatus {
   authenticated: boolean;
   username: string | null;
   email: string | null;
+  host: string | null;
+}
+
+export interface CopilotConfigChange {
+  model: string | null;
+}
+
+export interface LoginChallenge {
+  user_code: string;
+  verification_uri: string;
+}
+
+export type LoginEvent =
+  | { type: 'Challenge'; data: LoginChallenge }
+  | { type: 'Succeeded'; data: AuthStatus }
+  | { type: 'Failed'; data: string }
+  | { type: 'TimedOut' };
+
+export interface Account {
+  host: string;
+  login: string;
+  email: string | null;
 }
 
 export interface CopilotMessage {
@@ -18,6 +40,8 @@ export interface ModelInfo {
   name: string;
   provider: string;
   description: string;
+  premium_multiplier: number | null;
+  available: boolean | null;
 }
 
 export interface SessionInfo {
@@ -28,6 +52,17 @@ export interface SessionInfo {
   mode: AgentMode;
   created_at: number;
   is_active: boolean;
+  copilot_session_id: string | null;
+  crash: CrashReport | null;
+  account: Account | null;
+}
+
+export interface CrashReport {
+  code: number | null;
+  signal: string | null;
+  duration: number;
+  crashed_at: number;
+  last_lines: string[];
 }
 
 export interface CopilotStatus {
@@ -35,6 +70,41 @@ export interface CopilotStatus {
   path: string | null;
   version: string | null;
   authenticated: boolean;
+  supported: boolean;
+  unsupported_flags: string[];
+  error: string | null;
+}
+
+export interface ToolPolicy {
+  allow: string[];
+  deny: string[];
+  allow_all_paths: boolean;
+}
+
+export interface SpawnProfile {
+  env: Record<string, string>;
+  path_prepend: string[];
+  env_file: string | null;
+  add_dirs: string[];
+  extra_args: string[];
+}
+
+export interface ShutdownTimeouts {
+  exit_timeout_ms: number;
+  term_timeout_ms: number;
+}
+
+export interface RestartPolicy {
+  enabled: boolean;
+  max_restarts: number;
+  initial_backoff_ms: number;
+  max_backoff_ms: number;
+  stable_after_ms: number;
+}
+
+export interface UsageSettings {
+  premium_request_limit: number | null;
+  warn_at_percent: number;
 }
 
 export interface AppConfig {
@@ -49,9 +119,55 @@ export interface AppConfig {
   send_on_enter: boolean;
   notification_sound: boolean;
   accent_color: string;
+  tool_policy: ToolPolicy;
+  project_policies: Record<string, ToolPolicy>;
+  shutdown: ShutdownTimeouts;
+  restart: RestartPolicy;
+  spawn_profile: SpawnProfile;
+  project_profiles: Record<string, SpawnProfile>;
+  usage: UsageSettings;
+  /** Plugin registry index for the marketplace: an https:// URL or a local file */
+  plugin_registry: string | null;
+  /** Directory installed plugins are read from; null uses ~/.copilot/plugins */
+  plugins_dir: string | null;
+}
+
+export type McpHealth = 'healthy' | 'failing';
+
+export interface McpTool {
+  name: string;
+  description: string | null;
+}
+
+export interface McpResource {
+  uri: string;
+  name: string | null;
+  description: string | null;
+  mime_type: string | null;
+}
+
+export interface McpPrompt {
+  name: string;
+  description: string | null;
+}
+
+export interface McpProbeResult {
+  name: string;
+  health: McpHealth;
+  server_info: string | null;
+  protocol_version: string | null;
+  /** Time from launch to the `initialize` response, in milliseconds */
+  startup_ms: number | null;
+  tools: McpTool[];
+  resources: McpResource[];
+  prompts: McpPrompt[];
+  error: string | null;
+  stderr_tail: string[];
+  checked_at: number;
 }
 
 export interface UsageMetrics {
+  /** Each prompt counts its model's premium multiplier, so this can be fractional */
   premium_requests_used: number;
   premium_requests_limit: number | null;
   session_messages: number;
@@ -59,6 +175,27 @@ export interface UsageMetrics {
   active_model: string;
 }
 
+export interface UsageBucket {
+  key: string;
+  premium_requests: number;
+  messages: number;
+  input_tokens: number;
+  output_tokens: number;
+}
+
+export interface UsageSummary {
+  by_day: UsageBucket[];
+  by_project: UsageBucket[];
+  by_model: UsageBucket[];
+  month_premium_requests: number;
+  premium_requests_limit: number | null;
+}
+
+export interface UsageWarning {
+  used: number;
+  limit: number;
+}
+
 export interface KeyboardShortcut {
   key: string;
   description: string;
@@ -72,13 +209,148 @@ export type ParsedOutput =
   | { type: 'FileChange'; data: { path: string; action: string } }
   | { type: 'Thinking'; data: string }
   | { type: 'Error'; data: string }
-  | { type: 'RawLine'; data: string };
+  | { type: 'RawLine'; data: string }
+  | { type: 'PermissionRequest'; data: { tool: string; command: string; options: string[] } };
+
+export type SessionEvent =
+  | { type: 'Message'; data: { role: 'user' | 'assistant'; content: string } }
+  | { type: 'ToolCall'; data: { call_id: string; tool: string; arguments: unknown } }
+  | { type: 'ToolResult'; data: { call_id: string; success: boolean; output: string } }
+  | { type: 'FileEdit'; data: { call_id: string; path: string } }
+  | {
+      type: 'Usage';
+      data: { model: string | null; input_tokens: number | null; output_tokens: number | null };
+    }
+  | { type: 'Error'; data: { message: string } };
+
+export type SessionEventRecord = { id: string; timestamp: string } & SessionEvent;
+
+export interface ToolRequest {
+  toolCallId: string;
+  name: string;
+  arguments: unknown;
+}
+
+/** Event kinds of a Copilot CLI `events.jsonl`; data keeps the CLI's camelCase keys */
+export type CopilotEvent =
+  | {
+      type: 'session.start';
+      data: {
+        sessionId: string;
+        copilotVersion: string | null;
+        startTime: string | null;
+        context: {
+          cwd: string | null;
+          gitRoot: string | null;
+          repository: string | null;
+          branch: string | null;
+        } | null;
+      };
+    }
+  | { type: 'session.resume'; data: { resumeTime: string | null; eventCount: number | null } }
+  | { type: 'session.info'; data: { infoType: string | null; message: string } }
+  | { type: 'session.error'; data: { errorType: string | null; message: string } }
+  | { type: 'session.model_change'; data: { previousModel: string | null; newModel: string } }
+  | { type: 'session.compaction_start'; data: { preCompactionTokens: number | null } }
+  | {
+      type: 'session.compaction_complete';
+      data: {
+        success: boolean;
+        preCompactionTokens: number | null;
+        postCompactionTokens: number | null;
+        summaryContent: string | null;
+      };
+    }
+  | {
+      type: 'user.message';
+      data: {
+        content: string;
+        transformedContent: string | null;
+        attachments: unknown[];
+        source: string | null;
+      };
+    }
+  | { type: 'assistant.turn_start' | 'assistant.turn_end'; data: { turnId: string } }
+  | {
+      type: 'assistant.message';
+      data: {
+        messageId: string;
+        content: string;
+        toolRequests: ToolRequest[];
+        parentToolCallId: string | null;
+      };
+    }
+  | { type: 'assistant.reasoning'; data: { reasoningId: string; content: string } }
+  | {
+      type: 'assistant.usage';
+      data: {
+        model: string | null;
+        inputTokens: number | null;
+        outputTokens: number | null;
+        cacheReadTokens: number | null;
+        cacheWriteTokens: number | null;
+        cost: number | null;
+        duration: number | null;
+      };
+    }
+  | { type: 'tool.execution_start'; data: { toolCallId: string; toolName: string; arguments: unknown } }
+  | {
+      type: 'tool.execution_complete';
+      data: {
+        toolCallId: string;
+        success: boolean;
+        result: { content: string; detailedContent: string | null } | null;
+        error: { errorType: string | null; message: string } | null;
+      };
+    }
+  | { type: 'abort'; data: { reason: string | null } }
+  /** Kinds this app doesn't model, passed through under the CLI's own `type` */
+  | { type: string; data: unknown };
+
+export type TimelineEntry = { id: string; timestamp: string; parent_id: string | null } & CopilotEvent;
+
+export interface TimelinePage {
+  entries: TimelineEntry[];
+  total: number;
+  offset: number;
+}
 
 export type PtyEvent =
   | { type: 'Output'; data: string }
   | { type: 'Parsed'; data: ParsedOutput }
   | { type: 'Error'; data: string }
-  | { type: 'Exit'; data: number };
+  | {
+      type: 'SessionExited';
+      data: { code: number | null; signal: string | null; duration: number };
+    }
+  | { type: 'SessionCrashed'; data: { report: CrashReport; restart_in: number | null } };
+
+export type NamedKey =
+  | 'Enter'
+  | 'Tab'
+  | 'Esc'
+  | 'Backspace'
+  | 'Space'
+  | 'Up'
+  | 'Down'
+  | 'Left'
+  | 'Right'
+  | 'Ctrl-C'
+  | 'Ctrl-D';
+
+export type KeyInput =
+  | { type: 'Key'; data: NamedKey }
+  | { type: 'Text'; data: string }
+  | { type: 'Bytes'; data: number[] };
+
+export type SequencedPtyEvent = PtyEvent & { seq: number };
+
+export interface SessionOutput {
+  events: SequencedPtyEvent[];
+  raw: string;
+  next_seq: number;
+  truncated: boolean;
+}
 
 export type AppView = 'chat' | 'settings' | 'mcp' | 'plugins';
 
@@ -126,13 +398,42 @@ export interface PluginInfo {
   downloads: number | null;
 }
 
+export type McpTransport = 'local' | 'http' | 'sse';
+
+/** `project` servers come from `.copilot/mcp-config.json` in the working directory and
+ * take precedence over `global` ones with the same name */
+export type McpScope = 'global' | 'project';
+
 export interface McpServerConfig {
   name: string;
+  scope: McpScope;
+  transport: McpTransport;
   command: string;
   args: string[];
+  /** Values may be `${env:NAME}` or `${file:path}` references, resolved when a session
+   * starts. Credential-like literals are listed as `********`; sending that back keeps
+   * the stored value. */
   env: Record<string, string> | null;
+  url: string | null;
+  /** Same references and redaction as `env` */
+  headers: Record<string, string> | null;
   enabled: boolean;
   status: string | null;
+  /** e.g. tokens stored in plain text */
+  warnings: string[];
+}
+
+export type McpImportSource = 'vscode' | 'claude-desktop' | 'cursor';
+
+export interface McpImportCandidate {
+  source: McpImportSource;
+  /** Config file the server was found in */
+  path: string;
+  server: McpServerConfig;
+  /** Scope of the existing server with this name, if any */
+  conflict: McpScope | null;
+  /** What didn't carry over, e.g. VS Code `${input:...}` prompts */
+  warnings: string[];
 }
 
 export interface CopilotSession {